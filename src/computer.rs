@@ -1,69 +1,78 @@
 use nogo::Player;
 use nogo::Nogo;
 use nogo::NogoError;
+use nogo::save_header;
 
-#[derive(Debug, Copy, Clone)]
+use game_board::GameBoard;
+use game_board::is_dimension;
+
+use std::fmt;
+
+/// How a computer player picks its moves. Implement this to play with a
+/// different AI and hand it to Computer::with_strategy.
+pub trait Strategy: fmt::Debug {
+    /// Move to play for player on board. None passes.
+    fn next_move(&mut self, board: &GameBoard, player: &Player) -> Result<Option<(usize, usize)>, NogoError>;
+
+    /// One line saying why next_move would pick its move, for --explain-ai.
+    /// Must not change the moves the strategy goes on to make.
+    fn explain(&self, _board: &GameBoard, _player: &Player) -> Result<String, NogoError> {
+        Ok(String::from("no explanation"))
+    }
+
+    /// Row, column, and counter written to save files. Only
+    /// DeterministicStrategy carries on from them when a game is loaded.
+    fn progress(&self) -> (usize, usize, usize) {
+        (0, 0, 0)
+    }
+}
+
+/// A computer player. Plays DeterministicStrategy unless made with another
+/// strategy.
+#[derive(Debug)]
 pub struct Computer {
-    row:            usize,
-    column:         usize,
-    mult_factor:    usize,
-    height:         usize,
-    width:          usize,
-    counter:        usize,
-    b:              usize,
+    strategy: Box<dyn Strategy>,
 }
 
 impl Computer {
     /// Creates a new computer from arguments. If there should be a computer
     /// player function returns Some(Computer) otherwise returns None. There
     /// should be a computer player when player1_type or player2_type is
-    /// equal to 'c'.
+    /// equal to 'c'. Also None until nogo has a usable board size, such as
+    /// before a save file is loaded.
     pub fn new(nogo: &Nogo, player: Player) -> Option<Computer> {
-        let initial_row;
-        let initial_column;
-        let mult_factor;
+        if !Computer::is_computer(nogo, player) || !Computer::has_size(nogo) {
+            return None;
+        }
 
-        match player {
-            Player::O => {
-                if nogo.get_p1type() == 'h' {
-                    return None;
-                }
-                initial_row     = 1;
-                initial_column  = 4;
-                mult_factor     = 29;
-            },
-            Player::X => {
-                if nogo.get_p2type() == 'h' {
-                    return None;
-                }
-                initial_row     = 2;
-                initial_column  = 10;
-                mult_factor     = 17;
-            },
-        };
+        Some(Computer::with_strategy(Box::new(DeterministicStrategy::new(nogo, player))))
+    }
 
-        Some(Computer {
-            row:            initial_row,
-            column:         initial_column,
-            mult_factor:    mult_factor,
-            height:         nogo.get_height(),
-            width:          nogo.get_width(),
-            counter:        0,
-            b:              initial_row * nogo.get_width() + initial_column,
-        })
+    /// Creates a computer that plays strategy.
+    pub fn with_strategy(strategy: Box<dyn Strategy>) -> Computer {
+        Computer { strategy }
+    }
+
+    /// Gives back the strategy the computer plays, as it is now.
+    pub fn into_strategy(self) -> Box<dyn Strategy> {
+        self.strategy
     }
 
     /// Creates a Computer from a formatted line in a save file.
     pub fn load(nogo: &Nogo, file_line: &str, player: Player) 
         -> Result<Option<Computer>, NogoError> 
     {
-        let parsed: Vec<usize> = file_line.split_whitespace()
-                                          .map(|u| u.parse().unwrap())
-                                          .collect();
+        let parsed = save_header(file_line)?.split_whitespace()
+                                            .map(|u| u.parse())
+                                            .collect::<Result<Vec<usize>, _>>()
+                                            .map_err(|_| NogoError::corrupt(1, "header has something other than numbers"))?;
 
         let mut iter = parsed.iter().take(2);
-        let height  = *iter.next().ok_or(NogoError::CorruptFile)?;
-        let width   = *iter.next().ok_or(NogoError::CorruptFile)?;
+        let height  = *iter.next().ok_or(NogoError::corrupt(1, "header missing height"))?;
+        let width   = *iter.next().ok_or(NogoError::corrupt(1, "header missing width"))?;
+        if !is_dimension(height) || !is_dimension(width) {
+            return Err(NogoError::corrupt(1, format!("header says the board is {}x{}", height, width)));
+        }
 
         let mut iter = match player {
             Player::O => {
@@ -74,25 +83,264 @@ impl Computer {
             },
         };
 
-        let row     = *iter.next().ok_or(NogoError::CorruptFile)?;
-        let column  = *iter.next().ok_or(NogoError::CorruptFile)?;
-        let counter = *iter.next().ok_or(NogoError::CorruptFile)?;
-
-        let computer = Computer::new(nogo, player);
-
-        let mut c;
-        if computer.is_some() {
-            c = computer.unwrap();
-            c.row       = row;
-            c.column    = column;
-            c.counter   = counter;
-            c.height    = height;
-            c.width     = width;
-        } else {
+        let missing = |field| NogoError::corrupt(1, format!("header missing {} computer's {}", player, field));
+        let row     = *iter.next().ok_or_else(|| missing("row"))?;
+        let column  = *iter.next().ok_or_else(|| missing("column"))?;
+        let counter = *iter.next().ok_or_else(|| missing("counter"))?;
+
+        if !Computer::is_computer(nogo, player) {
             return Ok(None);
         }
 
-        Ok(Some(c))
+        let mut strategy = DeterministicStrategy::resume(nogo, player, row, column, counter);
+        strategy.height  = height;
+        strategy.width   = width;
+
+        Ok(Some(Computer::with_strategy(Box::new(strategy))))
+    }
+
+    /// Same as new but carries on the move sequence from a saved row, column,
+    /// and counter.
+    pub fn resume(nogo: &Nogo, player: Player, row: usize, column: usize, counter: usize)
+        -> Option<Computer>
+    {
+        if !Computer::is_computer(nogo, player) || !Computer::has_size(nogo) {
+            return None;
+        }
+
+        let strategy = DeterministicStrategy::resume(nogo, player, row, column, counter);
+        Some(Computer::with_strategy(Box::new(strategy)))
+    }
+
+    /// Picks the computer's next move for player on board, looking one move
+    /// ahead so whatever the strategy or strength it never plays a stone
+    /// that is left without liberties. None, a pass, if the strategy passes
+    /// or none of its next height * width moves survive.
+    pub fn choose_move(&mut self, board: &GameBoard, player: &Player) -> Result<Option<(usize, usize)>, NogoError> {
+        for _ in 0..board.get_height() * board.get_width() {
+            match self.strategy.next_move(board, player)? {
+                Some((h, w)) if self.survives(board, h, w, player)? => return Ok(Some((h, w))),
+                Some(_)                                            => continue,
+                None                                               => return Ok(None),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// One line saying why choose_move would pick its move.
+    pub fn explain(&self, board: &GameBoard, player: &Player) -> Result<String, NogoError> {
+        self.strategy.explain(board, player)
+    }
+
+    /// True if player's stone at (h, w) would keep a liberty or take the
+    /// last one of an opponent group next to it. False if the cell is taken
+    /// or off the board. NogoError::Internal if a touching group is too big
+    /// to check.
+    pub fn survives(&self, board: &GameBoard, h: usize, w: usize, player: &Player) -> Result<bool, NogoError> {
+        Ok(board.get(h, w) == Some('.') && !board.is_suicide(h, w, player)?)
+    }
+
+    pub fn get_row(&self) -> usize {
+        self.strategy.progress().0
+    }
+
+    pub fn get_column(&self) -> usize {
+        self.strategy.progress().1
+    }
+
+    pub fn get_counter(&self) -> usize {
+        self.strategy.progress().2
+    }
+
+    /// True if nogo's board size is one a move sequence can walk.
+    fn has_size(nogo: &Nogo) -> bool {
+        is_dimension(nogo.get_height()) && is_dimension(nogo.get_width())
+    }
+
+    /// True if nogo says player is played by the computer.
+    fn is_computer(nogo: &Nogo, player: Player) -> bool {
+        match player {
+            Player::O => nogo.get_p1type() == 'c',
+            Player::X => nogo.get_p2type() == 'c',
+        }
+    }
+}
+
+/// The original computer player. Walks the board in a fixed sequence
+/// starting from a different cell for each player, so games are always the
+/// same.
+#[derive(Debug, Copy, Clone)]
+pub struct DeterministicStrategy {
+    row:            usize,
+    column:         usize,
+    mult_factor:    usize,
+    height:         usize,
+    width:          usize,
+    counter:        usize,
+    b:              usize,
+    // 0 plays the raw move sequence. Each level up to MAX_STRENGTH skips
+    // more bad moves.
+    strength:       u8,
+    // True if moves that put an opponent group in atari are preferred.
+    tighten:        bool,
+    // Order STEPS are taken in between jumps, from --ai-seed.
+    order:          [usize; 4],
+}
+
+/// How well computer players play. Set with --difficulty in place of
+/// --strength.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Difficulty {
+    // The move sequence as it is.
+    Easy,
+    // Skips self captures.
+    Medium,
+    // Also prefers moves that put an opponent group in atari.
+    Hard,
+}
+
+impl Difficulty {
+    /// Difficulty called name on the command line.
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name {
+            "easy"   => Some(Difficulty::Easy),
+            "medium" => Some(Difficulty::Medium),
+            "hard"   => Some(Difficulty::Hard),
+            _        => None,
+        }
+    }
+}
+
+/// Where a computer's move sequence starts and how far every fifth move
+/// jumps. Set with --o-start and --x-start.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SequenceStart {
+    pub row:            usize,
+    pub column:         usize,
+    pub mult_factor:    usize,
+}
+
+impl SequenceStart {
+    /// Start player's sequence uses unless it is overridden.
+    pub fn default_for(player: Player) -> SequenceStart {
+        let (row, column, mult_factor) = match player {
+            Player::O => (1, 4, 29),
+            Player::X => (2, 10, 17),
+        };
+
+        SequenceStart { row, column, mult_factor }
+    }
+
+    /// Parses "row,column,mult_factor". None unless all three are numbers
+    /// below JUMP_MODULUS.
+    pub fn parse(text: &str) -> Option<SequenceStart> {
+        let parsed = text.split(',')
+                         .map(|n| n.trim().parse().ok().filter(|&n| n < JUMP_MODULUS))
+                         .collect::<Option<Vec<usize>>>()?;
+
+        match parsed[..] {
+            [row, column, mult_factor] => Some(SequenceStart { row, column, mult_factor }),
+            _                          => None,
+        }
+    }
+}
+
+/// Highest strength --strength accepts.
+pub const MAX_STRENGTH: u8 = 3;
+
+/// What each strength check guards against, in the order they are made.
+const CHECK_REASONS: [&str; 4] = ["an occupied cell", "self-capture", "a losing move", "atari"];
+
+/// Most moves of the sequence a computer looks at before settling for the
+/// best one it has seen.
+const MAX_CANDIDATES: usize = 10_000;
+
+/// Every fifth move jumps to (b + counter / 5 * mult_factor) modulo this.
+const JUMP_MODULUS: usize = 1_000_003;
+
+/// Rows and columns moved by the four moves between jumps, in the order
+/// they are taken without --ai-seed.
+const STEPS: [(usize, usize); 4] = [(1, 1), (2, 1), (1, 0), (0, 1)];
+
+/// Order of STEPS for an --ai-seed. 0 keeps the usual order, any other seed
+/// picks one of the other 23.
+pub fn step_order(seed: usize) -> [usize; 4] {
+    if seed == 0 {
+        return [0, 1, 2, 3];
+    }
+
+    // splitmix64 so neighbouring seeds pick unrelated orders.
+    let mut z = (seed as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+
+    // Read 1 to 23 as a factorial base number, each digit picking one of
+    // the steps not used yet.
+    let mut index = 1 + (z % 23) as usize;
+    let mut left  = vec![0, 1, 2, 3];
+    let mut order = [0; 4];
+    for (slot, &place) in order.iter_mut().zip(&[6, 2, 1, 1]) {
+        *slot = left.remove(index / place);
+        index %= place;
+    }
+
+    order
+}
+
+impl Strategy for DeterministicStrategy {
+    fn next_move(&mut self, board: &GameBoard, player: &Player) -> Result<Option<(usize, usize)>, NogoError> {
+        self.choose_move(board, player)
+    }
+
+    fn explain(&self, board: &GameBoard, player: &Player) -> Result<String, NogoError> {
+        self.explain_move(board, player)
+    }
+
+    fn progress(&self) -> (usize, usize, usize) {
+        (self.row, self.column, self.counter)
+    }
+}
+
+impl DeterministicStrategy {
+    /// Starts player's sequence for the board size, start, and strength in
+    /// nogo.
+    pub fn new(nogo: &Nogo, player: Player) -> DeterministicStrategy {
+        let start = nogo.get_sequence_start(player);
+
+        let (strength, tighten) = match nogo.get_difficulty() {
+            Some(Difficulty::Easy)      => (0, false),
+            Some(Difficulty::Medium)    => (1, false),
+            Some(Difficulty::Hard)      => (1, true),
+            None                        => (nogo.get_strength(), false),
+        };
+
+        DeterministicStrategy {
+            row:            start.row,
+            column:         start.column,
+            mult_factor:    start.mult_factor,
+            height:         nogo.get_height(),
+            width:          nogo.get_width(),
+            counter:        0,
+            b:              start.row * nogo.get_width() + start.column,
+            strength,
+            tighten,
+            order:          step_order(nogo.get_ai_seed()),
+        }
+    }
+
+    /// Same as new but carries on from a saved row, column, and counter.
+    pub fn resume(nogo: &Nogo, player: Player, row: usize, column: usize, counter: usize)
+        -> DeterministicStrategy
+    {
+        let mut strategy = DeterministicStrategy::new(nogo, player);
+
+        strategy.row        = row;
+        strategy.column     = column;
+        strategy.counter    = counter;
+
+        strategy
     }
 
     /// Gets computer's move. Automatically generates next move.
@@ -105,6 +353,179 @@ impl Computer {
         (r, c)
     }
 
+    /// Next move of the sequence that lands on an empty cell. None if the
+    /// board is full.
+    pub fn next_legal_move(&mut self, board: &GameBoard) -> Option<(usize, usize)> {
+        if board.is_full() {
+            return None;
+        }
+
+        let height = board.get_height();
+        let width  = board.get_width();
+
+        for _ in 0..4 * height * width {
+            let (h, w) = self.get_and_generate_move();
+            if board.get(h, w) == Some('.') {
+                return Some((h, w));
+            }
+        }
+
+        // The sequence keeps missing the few empty cells left. Take the first.
+        board.cells().find(|&(_, _, ch)| ch == '.').map(|(h, w, _)| (h, w))
+    }
+
+    /// Picks the computer's next move for player on board. At strength 0 this
+    /// is the next empty cell of the sequence even if it loses. Higher
+    /// strengths keep taking moves from the sequence until one is also not a
+    /// self capture (1), doesn't lose at all (2), and doesn't put an opponent
+    /// group in atari (3). If none is found in MAX_CANDIDATES moves the best
+    /// one seen is played, the first safe empty cell when none of them were
+    /// safe. When tightening, a move that also puts an opponent group in
+    /// atari without losing is played over the first that passes. The
+    /// sequence continues from the move played. None, a pass, if the board is
+    /// full or every empty cell is a suicide. NogoError::Internal if a group
+    /// is too big to check.
+    pub fn choose_move(&mut self, board: &GameBoard, player: &Player) -> Result<Option<(usize, usize)>, NogoError> {
+        if self.strength == 0 {
+            return Ok(self.next_legal_move(board));
+        }
+
+        let wanted = self.strength + 1;
+        let limit  = MAX_CANDIDATES.min(board.get_height() * board.get_width());
+
+        let mut probe = *self;
+        let first     = probe.get_and_generate_move();
+        let mut best  = (DeterministicStrategy::score(board, first.0, first.1, player, wanted)?, first, probe);
+        let mut found = self.tightens(board, first, best.0 == wanted, player)?;
+
+        for _ in 1..limit {
+            if found || (best.0 == wanted && !self.tighten) {
+                break;
+            }
+
+            let (h, w) = probe.get_and_generate_move();
+            let score  = DeterministicStrategy::score(board, h, w, player, wanted)?;
+
+            if self.tightens(board, (h, w), score == wanted, player)? {
+                best  = (score, (h, w), probe);
+                found = true;
+            } else if score > best.0 {
+                best = (score, (h, w), probe);
+            }
+        }
+
+        // Every move looked at was taken or a suicide.
+        if best.0 < 2 {
+            *self = probe;
+            for (h, w, ch) in board.cells() {
+                if ch == '.' && !board.is_suicide(h, w, player)? {
+                    return Ok(Some((h, w)));
+                }
+            }
+            return Ok(None);
+        }
+
+        *self = best.2;
+
+        Ok(Some(best.1))
+    }
+
+    /// One line saying why choose_move would pick its move: the first move of
+    /// the sequence it skipped and why, and the liberties the chosen stone
+    /// ends up with. Works on a copy so the computer's moves don't change.
+    pub fn explain_move(&self, board: &GameBoard, player: &Player) -> Result<String, NogoError> {
+        let wanted    = if self.strength == 0 { 0 } else { self.strength + 1 };
+        let mut after = *self;
+        let (h, w)    = match after.choose_move(board, player)? {
+            Some(cell) => cell,
+            None       => return Ok(String::from("no empty cells, passing")),
+        };
+
+        // Every move taken from the sequence before the chosen one was skipped.
+        let mut probe   = *self;
+        let mut skipped = Vec::new();
+        while probe.counter + 1 < after.counter {
+            let (sh, sw) = probe.get_and_generate_move();
+            skipped.push((sh, sw, DeterministicStrategy::score(board, sh, sw, player, wanted)?));
+        }
+
+        let mut line = String::new();
+        if let Some(&(sh, sw, score)) = skipped.first() {
+            // Every strength skips occupied cells, even 0 which makes no
+            // checks. Otherwise it passed every check but didn't tighten.
+            let reason = if board.get(sh, sw) != Some('.') {
+                CHECK_REASONS[0]
+            } else {
+                CHECK_REASONS.get(score as usize).filter(|_| score < wanted).unwrap_or(&"a quiet move")
+            };
+            line += &format!("avoiding {} at ({}, {})", reason, sh, sw);
+            match skipped.len() {
+                1 => {},
+                2 => line += " and 1 other move",
+                n => line += &format!(" and {} other moves", n - 1),
+            }
+            line += ", ";
+        }
+
+        line += &format!("chose ({}, {})", h, w);
+
+        let score = DeterministicStrategy::score(board, h, w, player, wanted)?;
+        if score < wanted {
+            line += &format!(" despite {}", CHECK_REASONS[score as usize]);
+        }
+
+        let mut next = board.clone();
+        if next.insert_move(h, w, player).is_ok() {
+            let liberties = next.analyze_groups()
+                                .into_iter()
+                                .find(|g| g.cells.contains(&(h, w)))
+                                .map_or(0, |g| g.liberties);
+            line += &format!(" with {} liberties", liberties);
+        }
+
+        Ok(line)
+    }
+
+    /// True if this computer is tightening and the move, which passed the
+    /// strength checks, puts an opponent group in atari without losing.
+    fn tightens(&self, board: &GameBoard, (h, w): (usize, usize), passed: bool, player: &Player)
+        -> Result<bool, NogoError>
+    {
+        Ok(self.tighten && passed
+                        && board.puts_in_atari(h, w, player)
+                        && !board.is_losing_move(h, w, player)?)
+    }
+
+    /// Number of the strength checks in order that (h, w) passes, up to
+    /// wanted. Occupied cells score 0.
+    fn score(board: &GameBoard, h: usize, w: usize, player: &Player, wanted: u8) -> Result<u8, NogoError> {
+        let checks: [&dyn Fn() -> Result<bool, NogoError>; 4] = [
+            &|| Ok(board.get(h, w) == Some('.')),
+            &|| Ok(!board.is_suicide(h, w, player)?),
+            &|| Ok(!board.is_losing_move(h, w, player)?),
+            &|| Ok(board.is_quiet_move(h, w, player)),
+        ];
+
+        let mut passed = 0;
+        for check in checks.iter().take(wanted as usize) {
+            if !check()? {
+                break;
+            }
+            passed += 1;
+        }
+
+        Ok(passed)
+    }
+
+    /// Generates the next steps moves and checks every one of them is on the
+    /// board. Moves wrap around the board so this should always be true.
+    pub fn verify_move_sequence(&mut self, board: &GameBoard, steps: usize) -> bool {
+        (0..steps).all(|_| {
+            let (h, w) = self.get_and_generate_move();
+            h < board.get_height() && w < board.get_width()
+        })
+    }
+
     pub fn get_row(&self) -> usize {
         self.row
     }
@@ -117,36 +538,80 @@ impl Computer {
         self.counter
     }
 
-    /// Generates next move based off counter. Stores move in Computer.
+    /// Generates next move based off counter. Stores move in Computer. Every
+    /// step wraps or is reduced modulo JUMP_MODULUS first so long games and
+    /// loaded counters never overflow.
     fn generate_next_move(&mut self) {
-        self.counter += 1;
+        self.counter = self.counter.wrapping_add(1);
         match self.counter % 5 {
-            1 => {
-                self.row    += 1;
-                self.column += 1;
+            0 => {
+                let jumps   = self.counter / 5 % JUMP_MODULUS;
+                let n       = (self.b % JUMP_MODULUS + jumps * (self.mult_factor % JUMP_MODULUS)) % JUMP_MODULUS;
+                self.row    = n / self.width;
+                self.column = n % self.width;
             },
 
-            2 => {
-                self.row    += 2;
-                self.column += 1;
+            step => {
+                let (rows, columns) = STEPS[self.order[step - 1]];
+                self.row    = self.row.wrapping_add(rows);
+                self.column = self.column.wrapping_add(columns);
             },
+        }
+    }
+}
 
-            3 => {
-                self.row    += 1;
-                self.column += 0;
-            },
+/// Plays a random empty cell each turn. The same seed always picks the same
+/// moves on the same boards.
+#[derive(Debug, Copy, Clone)]
+pub struct RandomStrategy {
+    // xorshift64 state, never 0.
+    state: u64,
+}
 
-            4 => {
-                self.row    += 0;
-                self.column += 1;
-            },
+impl RandomStrategy {
+    pub fn new(seed: u64) -> RandomStrategy {
+        // xorshift gets stuck on 0.
+        RandomStrategy { state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed } }
+    }
 
-            _ => {  // self.counter % 5 == 0
-                let n       = (self.b + self.counter / 5 * self.mult_factor) % 1_000_003;
-                self.row    = n / self.width;
-                self.column = n % self.width;
-            },
+    /// Next number from the generator.
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Random number below n with every value equally likely.
+    fn below(&mut self, n: u64) -> u64 {
+        // Numbers past the last whole multiple of n would favour the small
+        // values.
+        let limit = u64::MAX - u64::MAX % n;
+        loop {
+            let r = self.next_u64();
+            if r < limit {
+                return r % n;
+            }
+        }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn next_move(&mut self, board: &GameBoard, _player: &Player) -> Result<Option<(usize, usize)>, NogoError> {
+        let empty: Vec<(usize, usize)> = board.cells()
+            .filter(|&(_, _, ch)| ch == '.')
+            .map(|(h, w, _)| (h, w))
+            .collect();
+
+        if empty.is_empty() {
+            return Ok(None);
         }
+
+        Ok(Some(empty[self.below(empty.len() as u64) as usize]))
+    }
+
+    fn explain(&self, _board: &GameBoard, _player: &Player) -> Result<String, NogoError> {
+        Ok(String::from("picking a random empty cell"))
     }
 }
 
@@ -157,14 +622,17 @@ mod test {
     // First 12 moves a computer O should try on a 7x7 board.
     #[test]
     fn test_computer() {
-        let mut computer = Computer {
+        let mut computer = DeterministicStrategy {
             row:            1,
             column:         4,
             mult_factor:    29,
             height:         7,
             width:          7,
             counter:        0,
-            b:              1 * 7 + 4,
+            b:              7 + 4,
+            strength:       0,
+            tighten:        false,
+            order:          [0, 1, 2, 3],
         };
 
         assert_eq!((1, 4), computer.get_and_generate_move());
@@ -181,4 +649,378 @@ mod test {
         assert_eq!((3, 0), computer.get_and_generate_move());
         assert_eq!((5, 1), computer.get_and_generate_move());
     }
+
+    #[test]
+    fn test_ai_seed() {
+        let sequence = |seed: &str| {
+            let nogo = Nogo::from_args(vec!["nogors", "--ai-seed", seed, "c", "c", "7", "7"]
+                                           .into_iter().map(String::from)).unwrap();
+            let mut computer = DeterministicStrategy::new(&nogo, Player::O);
+            (0..13).map(|_| computer.get_and_generate_move()).collect::<Vec<(usize, usize)>>()
+        };
+
+        // Seed 0 is the sequence test_computer checks.
+        assert_eq!(vec![(1, 4), (2, 5), (4, 6), (5, 6), (5, 0), (5, 5), (6, 6),
+                        (1, 0), (2, 0), (2, 1), (2, 6), (3, 0), (5, 1)], sequence("0"));
+
+        // Other seeds go their own way between jumps but always the same way.
+        let seeded = sequence("7");
+        assert_ne!(sequence("0")[..5], seeded[..5]);
+        assert_eq!(seeded, sequence("7"));
+        assert_eq!(sequence("0")[5], seeded[5]);
+
+        assert_eq!([0, 1, 2, 3], step_order(0));
+        for seed in 1..200 {
+            let mut order = step_order(seed);
+            assert_ne!([0, 1, 2, 3], order);
+            order.sort();
+            assert_eq!([0, 1, 2, 3], order);
+        }
+    }
+
+    #[test]
+    fn test_verify_move_sequence() {
+        for &(height, width) in &[(4, 4), (1000, 1000)] {
+            let board = GameBoard::new(height, width).unwrap();
+            let mut computer = DeterministicStrategy {
+                row:            2,
+                column:         10,
+                mult_factor:    17,
+                height,
+                width,
+                counter:        0,
+                b:              2 * width + 10,
+                strength:       0,
+                tighten:        false,
+                order:          [0, 1, 2, 3],
+            };
+
+            assert!(computer.verify_move_sequence(&board, 100_000));
+        }
+    }
+
+    #[test]
+    fn test_strength() {
+        let nogo     = Nogo::from_args(vec!["nogors", "c", "h", "7", "7"].into_iter().map(String::from)).unwrap();
+        let mut raw  = DeterministicStrategy::new(&nogo, Player::O);
+        let mut weak = raw;
+        let board    = GameBoard::new(7, 7).unwrap();
+
+        for _ in 0..50 {
+            assert_eq!(Some(raw.get_and_generate_move()), weak.choose_move(&board, &Player::O).unwrap());
+        }
+
+        // O's first move, (1, 4), would be surrounded and its second, (2, 5),
+        // is taken.
+        let board = GameBoard::from("....X..\n\
+                                     ...X.X.\n\
+                                     ....XX.\n\
+                                     .......\n\
+                                     .......\n\
+                                     .......\n\
+                                     .......\n").unwrap();
+        let mut weak   = DeterministicStrategy::new(&nogo, Player::O);
+        let mut strong = weak;
+        strong.strength = 1;
+
+        // Strength 0 only skips the taken cell.
+        assert_eq!(Some((1, 4)), weak.choose_move(&board, &Player::O).unwrap());
+
+        let (h, w) = strong.choose_move(&board, &Player::O).unwrap().unwrap();
+        assert_eq!((4, 6), (h, w));
+        assert_eq!(3, strong.get_counter());
+        assert!(!board.would_self_capture(h, w, &Player::O).unwrap());
+
+        // The same sequence from the next move on.
+        assert_eq!(Some((4, 6)), weak.choose_move(&board, &Player::O).unwrap());
+        assert_eq!(weak.choose_move(&board, &Player::O).unwrap(), strong.choose_move(&board, &Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_strength_no_safe_move() {
+        let nogo = Nogo::from_args(vec!["nogors", "c", "h", "4", "4"].into_iter().map(String::from)).unwrap();
+
+        // Both holes are O suicides while the X group keeps the other one.
+        let board = GameBoard::from("X.XX\n\
+                                     XXXX\n\
+                                     XX.X\n\
+                                     XXXX\n").unwrap();
+        let mut weak   = DeterministicStrategy::new(&nogo, Player::O);
+        let mut strong = weak;
+        strong.strength = 1;
+
+        // Strength 0 plays a suicide but strength 1 passes.
+        let (h, w) = weak.choose_move(&board, &Player::O).unwrap().unwrap();
+        assert!(board.is_suicide(h, w, &Player::O).unwrap());
+        assert_eq!(None, strong.choose_move(&board, &Player::O).unwrap());
+
+        // With one safe cell left it is played.
+        let board = GameBoard::from("X..X\n\
+                                     XXXX\n\
+                                     XX.X\n\
+                                     XXXX\n").unwrap();
+        let mut strong = DeterministicStrategy::new(&nogo, Player::O);
+        strong.strength = 1;
+        let (h, w) = strong.choose_move(&board, &Player::O).unwrap().unwrap();
+        assert!(!board.is_suicide(h, w, &Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_next_legal_move() {
+        let nogo  = Nogo::from_args(vec!["nogors", "c", "h", "7", "7"].into_iter().map(String::from)).unwrap();
+        let board = GameBoard::from(".......\n\
+                                     ....O..\n\
+                                     .....O.\n\
+                                     .......\n\
+                                     ......O\n\
+                                     .......\n\
+                                     .......\n").unwrap();
+
+        // (1, 4), (2, 5), and (4, 6) are taken.
+        let mut computer = DeterministicStrategy::new(&nogo, Player::O);
+        assert_eq!(Some((5, 6)), computer.next_legal_move(&board));
+        assert_eq!(4, computer.get_counter());
+
+        // Fills the board one cell at a time without proposing a taken one.
+        let mut board = GameBoard::new(4, 4).unwrap();
+        let nogo      = Nogo::from_args(vec!["nogors", "c", "h", "4", "4"].into_iter().map(String::from)).unwrap();
+        let mut computer = DeterministicStrategy::new(&nogo, Player::O);
+
+        for _ in 0..16 {
+            let (h, w) = computer.next_legal_move(&board).unwrap();
+            board.insert_move(h, w, &Player::O).unwrap();
+        }
+
+        assert!(board.is_full());
+        assert_eq!(None, computer.next_legal_move(&board));
+        assert_eq!(None, computer.choose_move(&board, &Player::O).unwrap());
+        assert_eq!("no empty cells, passing", computer.explain(&board, &Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_explain() {
+        let nogo  = Nogo::from_args(vec!["nogors", "c", "h", "7", "7"].into_iter().map(String::from)).unwrap();
+        let board = GameBoard::from("....X..\n\
+                                     ...X.X.\n\
+                                     ....XX.\n\
+                                     .......\n\
+                                     .......\n\
+                                     .......\n\
+                                     .......\n").unwrap();
+        let mut computer = DeterministicStrategy::new(&nogo, Player::O);
+
+        assert_eq!("chose (1, 4) with 0 liberties", computer.explain(&board, &Player::O).unwrap());
+
+        computer.strength = 1;
+        let before = computer;
+        assert_eq!("avoiding self-capture at (1, 4) and 1 other move, chose (4, 6) with 3 liberties",
+                   computer.explain(&board, &Player::O).unwrap());
+        assert_eq!(before.get_counter(), computer.get_counter());
+        assert_eq!(Some((4, 6)), computer.choose_move(&board, &Player::O).unwrap());
+
+        let board = GameBoard::new(7, 7).unwrap();
+        assert_eq!("chose (5, 6) with 3 liberties", computer.explain(&board, &Player::O).unwrap());
+
+        // Strength 0 still skips an occupied cell.
+        let mut board = GameBoard::new(7, 7).unwrap();
+        board.insert_move(1, 4, &Player::X).unwrap();
+        let computer = DeterministicStrategy::new(&nogo, Player::O);
+        assert_eq!("avoiding an occupied cell at (1, 4), chose (2, 5) with 4 liberties",
+                   computer.explain(&board, &Player::O).unwrap());
+    }
+
+    /// Plays a game between O using strategy o and the deterministic X
+    /// until someone wins. Returns every move in order.
+    fn play_out(o: &mut dyn Strategy, x: &mut dyn Strategy) -> Vec<(usize, usize)> {
+        let mut board  = GameBoard::new(5, 5).unwrap();
+        let mut player = Player::O;
+        let mut moves  = Vec::new();
+
+        loop {
+            let strategy: &mut dyn Strategy = match player {
+                Player::O => o,
+                Player::X => x,
+            };
+            let (h, w) = strategy.next_move(&board, &player).unwrap().unwrap();
+            board.insert_move(h, w, &player).unwrap();
+            moves.push((h, w));
+
+            if board.check_win_at(h, w).is_some() {
+                return moves;
+            }
+            player = if player == Player::O { Player::X } else { Player::O };
+        }
+    }
+
+    #[test]
+    fn test_random_strategy() {
+        let nogo = Nogo::from_args(vec!["nogors", "h", "c", "5", "5"].into_iter().map(String::from)).unwrap();
+        let x    = DeterministicStrategy::new(&nogo, Player::X);
+
+        let first  = play_out(&mut RandomStrategy::new(7), &mut x.clone());
+        let second = play_out(&mut RandomStrategy::new(7), &mut x.clone());
+        assert_eq!(first, second);
+
+        let other = play_out(&mut RandomStrategy::new(8), &mut x.clone());
+        assert_ne!(first, other);
+
+        // Only ever empty cells, and nothing once the board is full.
+        let mut random = RandomStrategy::new(0);
+        let mut board  = GameBoard::new(4, 4).unwrap();
+        for _ in 0..16 {
+            let (h, w) = random.next_move(&board, &Player::O).unwrap().unwrap();
+            board.insert_move(h, w, &Player::O).unwrap();
+        }
+        assert_eq!(None, random.next_move(&board, &Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_difficulty() {
+        let nogo = |difficulty| {
+            Nogo::from_args(vec!["nogors", "--difficulty", difficulty, "c", "h", "7", "7"]
+                                .into_iter().map(String::from)).unwrap()
+        };
+
+        // O's first move (1, 4) is safe but (4, 6) puts the X stone at (3, 6)
+        // in atari.
+        let board = GameBoard::from(".......\n\
+                                     .......\n\
+                                     .......\n\
+                                     .....OX\n\
+                                     .......\n\
+                                     .......\n\
+                                     .......\n").unwrap();
+
+        let mut easy   = DeterministicStrategy::new(&nogo("easy"), Player::O);
+        let mut medium = DeterministicStrategy::new(&nogo("medium"), Player::O);
+        let mut hard   = DeterministicStrategy::new(&nogo("hard"), Player::O);
+
+        assert_eq!(Some((1, 4)), easy.choose_move(&board, &Player::O).unwrap());
+        assert_eq!(Some((1, 4)), medium.choose_move(&board, &Player::O).unwrap());
+        assert_eq!(Some((4, 6)), hard.choose_move(&board, &Player::O).unwrap());
+        assert_eq!(3, hard.get_counter());
+        assert!(hard.explain_move(&GameBoard::new(7, 7).unwrap(), &Player::O).unwrap().starts_with("chose"));
+
+        // With nothing to put in atari hard plays like medium.
+        let board = GameBoard::new(7, 7).unwrap();
+        let mut medium = DeterministicStrategy::new(&nogo("medium"), Player::O);
+        let mut hard   = DeterministicStrategy::new(&nogo("hard"), Player::O);
+        assert_eq!(medium.choose_move(&board, &Player::O).unwrap(), hard.choose_move(&board, &Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_long_game() {
+        let mut computer = DeterministicStrategy {
+            row:            999,
+            column:         999,
+            mult_factor:    29,
+            height:         1000,
+            width:          1000,
+            counter:        0,
+            b:              999 * 1000 + 999,
+            strength:       0,
+            tighten:        false,
+            order:          [0, 1, 2, 3],
+        };
+
+        for _ in 0..50_000 {
+            let (h, w) = computer.get_and_generate_move();
+            assert!(h < 1000 && w < 1000);
+        }
+
+        // A counter loaded from a save can be anything.
+        computer.counter = usize::MAX - 7;
+        computer.row     = usize::MAX;
+        computer.column  = usize::MAX;
+        assert!(computer.verify_move_sequence(&GameBoard::new(1000, 1000).unwrap(), 50_000));
+    }
+
+    #[test]
+    fn test_survives() {
+        let nogo     = Nogo::from_args(vec!["nogors", "c", "h", "4", "4"].into_iter().map(String::from)).unwrap();
+        let computer = Computer::new(&nogo, Player::O).unwrap();
+
+        // (1, 1) is surrounded and (0, 1) is taken.
+        let board = GameBoard::from(".X..\n\
+                                     X.X.\n\
+                                     .X..\n\
+                                     ....\n").unwrap();
+        assert!(!computer.survives(&board, 1, 1, &Player::O).unwrap());
+        assert!(!computer.survives(&board, 0, 1, &Player::O).unwrap());
+        assert!(computer.survives(&board, 3, 3, &Player::O).unwrap());
+
+        // O at (1, 0) has no liberties but takes the last one of X at (0, 0).
+        let board = GameBoard::from("XO..\n\
+                                     .X..\n\
+                                     X...\n\
+                                     ....\n").unwrap();
+        assert!(computer.survives(&board, 1, 0, &Player::O).unwrap());
+        assert!(!computer.survives(&board, 4, 0, &Player::O).unwrap());
+
+        // Joining a group that still has a liberty elsewhere is fine, but not
+        // one whose last liberty it fills.
+        let board = GameBoard::from("O.X.\n\
+                                     XX..\n\
+                                     .O..\n\
+                                     O.O.\n").unwrap();
+        assert!(!computer.survives(&board, 0, 1, &Player::O).unwrap());
+        assert!(computer.survives(&board, 3, 1, &Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_choose_move_survives() {
+        let nogo = Nogo::from_args(vec!["nogors", "c", "h", "4", "4"].into_iter().map(String::from)).unwrap();
+
+        // Both holes are O suicides. The raw sequence plays one, a computer
+        // at the same strength passes.
+        let board = GameBoard::from("X.XX\n\
+                                     XXXX\n\
+                                     XX.X\n\
+                                     XXXX\n").unwrap();
+        let mut raw      = DeterministicStrategy::new(&nogo, Player::O);
+        let mut computer = Computer::new(&nogo, Player::O).unwrap();
+        assert!(raw.choose_move(&board, &Player::O).unwrap().is_some());
+        assert_eq!(None, computer.choose_move(&board, &Player::O).unwrap());
+
+        // A strategy that only suggests suicides passes too.
+        let mut random = Computer::with_strategy(Box::new(RandomStrategy::new(3)));
+        assert_eq!(None, random.choose_move(&board, &Player::O).unwrap());
+
+        // With one safe cell left it is played.
+        let board = GameBoard::from("X..X\n\
+                                     XXXX\n\
+                                     XX.X\n\
+                                     XXXX\n").unwrap();
+        let (h, w) = computer.choose_move(&board, &Player::O).unwrap().unwrap();
+        assert!(computer.survives(&board, h, w, &Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_needs_board_size() {
+        // A game started from a file has no size until the file is loaded.
+        let nogo = Nogo::from_args(vec!["nogors", "c", "c", "save.txt"].into_iter().map(String::from)).unwrap();
+        assert_eq!(0, nogo.get_width());
+        assert!(Computer::new(&nogo, Player::O).is_none());
+        assert!(Computer::resume(&nogo, Player::X, 1, 1, 0).is_none());
+
+        let nogo = Nogo::from_args(vec!["nogors", "c", "c", "7", "7"].into_iter().map(String::from)).unwrap();
+        assert!(Computer::load(&nogo, "nogo v1 0 7 0 1 4 0 2 10 0", Player::O).is_err());
+        assert!(Computer::load(&nogo, "nogo v1 7 1001 0 1 4 0 2 10 0", Player::X).is_err());
+        assert!(Computer::load(&nogo, "nogo v1 7 7 0 1 4 0 2 10 0", Player::O).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_load_bad_header() {
+        let nogo   = Nogo::from_args(vec!["nogors", "c", "c", "7", "7"].into_iter().map(String::from)).unwrap();
+        let reason = |line, player| match Computer::load(&nogo, line, player) {
+            Err(NogoError::CorruptFile { line: 1, reason }) => reason,
+            r => panic!("{:?} gave {:?}", line, r.map(|_| ())),
+        };
+
+        assert_eq!("header has something other than numbers", reason("nogo v1 7 7 0 1 a 0 2 10 0", Player::O));
+        assert_eq!("header has something other than numbers", reason("nogo v1 7 7 0 1 4 0 2 10 -1", Player::X));
+        assert_eq!("header missing width", reason("nogo v1 7", Player::O));
+        assert_eq!("header missing O computer's counter", reason("nogo v1 7 7 0 1 4", Player::O));
+        assert_eq!("header missing X computer's row", reason("nogo v1 7 7 0 1 4 0", Player::X));
+    }
 }
@@ -1,22 +1,170 @@
 use std::error;
+use std::fmt;
+use std::io;
+use std::io::Write;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
 
+use nogo::GameResult;
 use nogo::NogoError;
 use nogo::Player;
 
+/// Reasons a move can't be placed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MoveError {
+    RowOutOfBounds,
+    ColumnOutOfBounds,
+    Occupied,
+    Suicide,
+    // A touching group was bigger than the flood fill limit so the suicide
+    // rule couldn't be checked.
+    GroupTooBig,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MoveError::RowOutOfBounds       => write!(f, "Invalid row"),
+            MoveError::ColumnOutOfBounds    => write!(f, "Invalid column"),
+            MoveError::Occupied             => write!(f, "Position already taken"),
+            MoveError::Suicide              => write!(f, "Move would capture your own group"),
+            MoveError::GroupTooBig          => write!(f, "Group too big to check"),
+        }
+    }
+}
+
+impl error::Error for MoveError {}
+
+/// A connected group of one player's stones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupInfo {
+    pub color:      Player,
+    pub cells:      Vec<(usize, usize)>,
+    pub liberties:  usize,
+}
+
+/// How far a game has progressed, going by how full the board is.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Phase {
+    Opening,
+    Middlegame,
+    Endgame,
+}
+
+/// Where a cell sits on the board. Corners have 2 neighbours, edges 3, and
+/// interior cells 4.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CellKind {
+    Corner,
+    Edge,
+    Interior,
+}
+
+/// One of the four ways from a cell to a neighbour.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Direction {
+    Left,
+    Up,
+    Right,
+    Down,
+}
+
+impl Direction {
+    /// Every direction, in the order neighbors gives cells.
+    pub const ALL: [Direction; 4] = [Direction::Left, Direction::Up, Direction::Right, Direction::Down];
+
+    /// Cell one step this way from (h, w) on a height by width board, or
+    /// None if the step leaves it.
+    pub fn step(self, h: usize, w: usize, height: usize, width: usize) -> Option<(usize, usize)> {
+        match self {
+            Direction::Left     => w.checked_sub(1).map(|w| (h, w)),
+            Direction::Up       => h.checked_sub(1).map(|h| (h, w)),
+            Direction::Right    => Some((h, w + 1)).filter(|_| w + 1 < width),
+            Direction::Down     => Some((h + 1, w)).filter(|_| h + 1 < height),
+        }
+    }
+}
+
+/// Percentage of cells filled where the opening ends.
+pub const MIDDLEGAME_PERCENT: usize = 20;
+
+/// Percentage of cells filled past which it is the endgame.
+pub const ENDGAME_PERCENT: usize = 70;
+
+/// Most stones a single flood fill will visit by default. More than fit on the
+/// biggest board so it can only be reached by a corrupt board.
+pub const DEFAULT_FLOOD_LIMIT: usize = 2_000_000;
+
+/// Smallest and largest height or width a board can have.
+pub const MIN_DIMENSION: usize = 4;
+pub const MAX_DIMENSION: usize = 1000;
+
+/// Largest height or width SGF coordinates, a to z then A to Z, can reach.
+pub const SGF_MAX_DIMENSION: usize = 52;
+
 /// Holds game board.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GameBoard {
     height: usize,
     width:  usize,
     board:  Vec<Vec<char>>,
+
+    // Every placement made with insert_move in the order they were played.
+    history: Vec<(usize, usize, char)>,
+
+    // Most stones a single flood fill will visit before giving up.
+    flood_limit: usize,
+
+    // Whether insert_move rejects suicides instead of letting them lose.
+    forbid_suicide: bool,
+}
+
+/// Whether positions are decided, keyed by GameBoard::position_hash. Owned by
+/// whatever is searching so boards stay plain values.
+#[derive(Debug, Default)]
+pub struct DecidedCache {
+    decided: HashMap<u64, bool>,
+}
+
+impl DecidedCache {
+    pub fn new() -> DecidedCache {
+        DecidedCache::default()
+    }
+
+    /// Forgets every position remembered by GameBoard::is_decided_cached.
+    pub fn clear(&mut self) {
+        self.decided.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.decided.is_empty()
+    }
+}
+
+/// Boards are equal when they have the same stones in the same cells, however
+/// they got there.
+impl PartialEq for GameBoard {
+    fn eq(&self, other: &GameBoard) -> bool {
+        self.board == other.board
+    }
+}
+
+impl Eq for GameBoard {}
+
+/// Hashes the cells only, the same as PartialEq compares.
+impl Hash for GameBoard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.board.hash(state);
+    }
 }
 
 impl GameBoard {
     /// Create new game board with given dimensions.
     pub fn new(height: usize, width: usize) -> Result<GameBoard, NogoError> {
-        if height < 4 || height > 1000 || width < 4 || width > 1000 {
-            return Err(NogoError::InvalidDimension);
-        }
+        check_dimensions(height, width).map_err(|_| NogoError::InvalidDimension)?;
 
         let mut board = Vec::new();
 
@@ -28,37 +176,140 @@ impl GameBoard {
         }
 
         Ok(GameBoard {
-            height: height,
-            width:  width,
-            board:  board,
+            height,
+            width,
+            board,
+            history: Vec::new(),
+            flood_limit: DEFAULT_FLOOD_LIMIT,
+            forbid_suicide: false,
         })
     }
 
-    /// Create board from string version of board. (From a file).
+    /// Create new empty game board from dimensions written as "height width"
+    /// or "heightxwidth".
+    pub fn empty_from_dims(dims: &str) -> Result<GameBoard, NogoError> {
+        let parts: Vec<&str> = if dims.contains('x') {
+            dims.split('x').map(|d| d.trim()).collect()
+        } else {
+            dims.split_whitespace().collect()
+        };
+
+        if parts.len() != 2 {
+            return Err(NogoError::InvalidDimension);
+        }
+
+        let height = parts[0].parse().map_err(|_| NogoError::InvalidDimension)?;
+        let width  = parts[1].parse().map_err(|_| NogoError::InvalidDimension)?;
+
+        GameBoard::new(height, width)
+    }
+
+    /// Create board with given dimensions from its cells written one row after
+    /// another with no line breaks.
+    pub fn from_flat(height: usize, width: usize, cells: &str) -> Result<GameBoard, NogoError> {
+        let mut game = GameBoard::new(height, width)?;
+        let cells: Vec<char> = cells.chars().collect();
+
+        if cells.len() != height * width {
+            return Err(NogoError::InvalidDimension);
+        }
+
+        if let Some(ch) = cells.iter().find(|ch| !is_cell(**ch)) {
+            return Err(NogoError::corrupt(0, format!("'{}' isn't a cell", ch)));
+        }
+
+        game.board = cells.chunks(width).map(|line| line.to_vec()).collect();
+
+        Ok(game)
+    }
+
+    /// Same as from but returns NogoError::CorruptFile if the board isn't
+    /// height by width, such as when it disagrees with a save file's header.
+    pub fn from_with_dims(contents: &str, height: usize, width: usize)
+        -> Result<GameBoard, NogoError>
+    {
+        let board = GameBoard::from(contents)?;
+
+        if board.height != height || board.width != width {
+            return Err(NogoError::corrupt(0, format!("board is {}x{} but should be {}x{}",
+                                                     board.height, board.width, height, width)));
+        }
+
+        Ok(board)
+    }
+
+    /// Create board from string version of board. (From a file). Errors
+    /// give the line of contents the problem is on.
     pub fn from(contents: &str) -> Result<GameBoard, NogoError> {
         let mut board = Vec::new();
+        // Line of contents each row came from.
+        let mut lines = Vec::new();
+
+        for (number, line) in contents.lines().enumerate() {
+            for row in line.split_whitespace() {
+                let tmp_vec: Vec<char> = row.chars().collect();
+                board.push(tmp_vec);
+                lines.push(number + 1);
+            }
+        }
 
-        for line in contents.split_whitespace() {
-            let tmp_vec: Vec<char> = line.chars().collect();
-            board.push(tmp_vec);
+        if board.is_empty() {
+            return Err(NogoError::corrupt(0, "board has no rows"));
         }
 
         let height = board.len();
         let width  = board[0].len();
-        if height < 4 || height > 1000 || width < 4 || width > 1000 {
-            return Err(NogoError::CorruptFile);
+        check_dimensions(height, width).map_err(|reason| NogoError::corrupt(0, reason))?;
+
+        for (h, line) in board.iter().enumerate() {
+            // Every row must be as long as the first.
+            if line.len() != width {
+                return Err(NogoError::corrupt(lines[h], format!("row {} has wrong width", h)));
+            }
+
+            if let Some(ch) = line.iter().find(|ch| !is_cell(**ch)) {
+                return Err(NogoError::corrupt(lines[h], format!("row {} has '{}'", h, ch)));
+            }
         }
 
         Ok(GameBoard {
-            height: height,
-            width:  width,
-            board:  board,
+            height,
+            width,
+            board,
+            history: Vec::new(),
+            flood_limit: DEFAULT_FLOOD_LIMIT,
+            forbid_suicide: false,
+        })
+    }
+
+    /// Returns character at given coordiante if it exists. None if it is
+    /// off the board.
+    pub fn get(&self, h: usize, w: usize) -> Option<char> {
+        self.board.get(h).and_then(|line| line.get(w)).cloned()
+    }
+
+    /// Every cell as (h, w, contents), row by row.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, char)> + '_ {
+        self.board.iter().enumerate().flat_map(|(h, line)| {
+            line.iter().enumerate().map(move |(w, &ch)| (h, w, ch))
         })
     }
 
-    /// Returns character at given coordiante if it exists.
-    pub fn get(&self, h: usize, w: usize) -> char {
-        self.board[h][w]
+    /// Sets the most stones a single flood fill will visit before giving up.
+    pub fn set_flood_limit(&mut self, limit: usize) {
+        self.flood_limit = limit;
+    }
+
+    /// Makes insert_move reject suicides with MoveError::Suicide. Off by
+    /// default, a suicide just loses the game.
+    pub fn set_forbid_suicide(&mut self, forbid: bool) {
+        self.forbid_suicide = forbid;
+    }
+
+    /// Every placement made with insert_move as (row, column, player) in the
+    /// order they were played.
+    pub fn get_history(&self) -> &[(usize, usize, char)] {
+        &self.history
     }
 
     pub fn get_height(&self) -> usize {
@@ -71,71 +322,173 @@ impl GameBoard {
 
     /// Prints game board with borders around it.
     pub fn print(&self) {
-        // Top border.
-        print!("/");
-        for line in self.board.iter().take(1) {
-            for _ in line.iter() {
-                print!("-");
-            }
+        let stdout = io::stdout();
+        self.print_to(&mut stdout.lock()).unwrap();
+    }
+
+    /// Writes game board with borders around it to w.
+    pub fn print_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        // Built as one string and written at once. A write per cell takes
+        // seconds on the biggest boards.
+        w.write_all(self.render().as_bytes())
+    }
+
+    /// Checks (h, w) is on the board and empty without changing the board.
+    /// Rules that depend on who is moving are left to move_error.
+    pub fn can_place(&self, h: usize, w: usize) -> Result<(), MoveError> {
+        if h >= self.height {
+            Err(MoveError::RowOutOfBounds)
+        } else if w >= self.width {
+            Err(MoveError::ColumnOutOfBounds)
+        } else if self.board[h][w] == 'O' || self.board[h][w] == 'X' {
+            Err(MoveError::Occupied)
+        } else {
+            Ok(())
         }
-        println!("\\");
+    }
 
-        // Side borders and board.
-        for line in &self.board {
-            print!("|");
+    /// Returns why the player can't place a stone at (h, w), or None if they
+    /// can. Doesn't change the board. Every rule applies to both players
+    /// for now. NogoError::Internal if checking for suicide meets a group
+    /// bigger than the flood fill limit.
+    pub fn move_error(&self, h: usize, w: usize, player: &Player) -> Result<Option<MoveError>, NogoError> {
+        Ok(if let Err(e) = self.can_place(h, w) {
+            Some(e)
+        } else if self.forbid_suicide && self.would_self_capture(h, w, player)? {
+            Some(MoveError::Suicide)
+        } else {
+            None
+        })
+    }
 
-            for ch in line {
-                print!("{}", *ch);
+    /// Returns the game board with borders around it, the same as print
+    /// shows it.
+    pub fn render(&self) -> String {
+        self.render_with_empty('.')
+    }
+
+    /// Same as render_with_empty but with row numbers down the left and
+    /// column numbers along the top, zero based like insert_move. Column
+    /// numbers are written downwards one digit per line so they line up with
+    /// their cells.
+    pub fn render_labeled(&self, empty: char) -> String {
+        self.label(&self.render_with_empty(empty))
+    }
+
+    /// Adds row and column numbers, the same as render_labeled, to a board
+    /// from one of the other render functions.
+    pub fn label(&self, frame: &str) -> String {
+        let row_digits    = (self.height - 1).to_string().len();
+        let column_digits = (self.width - 1).to_string().len();
+        let mut labeled   = String::new();
+
+        for digit in 0..column_digits {
+            // Past the row numbers and the left border.
+            labeled.push_str(&" ".repeat(row_digits + 1));
+            for w in 0..self.width {
+                let number = format!("{:>1$}", w, column_digits);
+                labeled.push(number.as_bytes()[digit] as char);
             }
+            labeled.push('\n');
+        }
 
-            println!("|");
+        for (i, line) in frame.lines().enumerate() {
+            // First and last lines are the top and bottom borders.
+            let label = if i == 0 || i > self.height { String::new() } else { (i - 1).to_string() };
+            labeled.push_str(&format!("{:>1$}{2}\n", label, row_digits, line));
         }
 
-        // Bottom border.
-        print!("\\");
-        for line in self.board.iter().take(1) {
-            for _ in line.iter() {
-                print!("-");
+        labeled
+    }
+
+    /// Returns the game board with borders around it, showing empty cells as
+    /// the given character. Only changes how the board looks, saves always
+    /// use '.'.
+    pub fn render_with_empty(&self, empty: char) -> String {
+        self.frame(empty, None)
+    }
+
+    /// Same as render_with_empty but the stone placed last is shown in
+    /// lowercase so it stands out from the others.
+    pub fn render_highlight(&self, empty: char) -> String {
+        self.frame(empty, self.last_move())
+    }
+
+    /// Board with borders, empty cells shown as empty and the stone at
+    /// highlight in lowercase.
+    fn frame(&self, empty: char, highlight: Option<(usize, usize)>) -> String {
+        let border = "-".repeat(self.width);
+        let mut frame = String::with_capacity((self.width + 3) * (self.height + 2));
+
+        frame.push('/');
+        frame.push_str(&border);
+        frame.push_str("\\\n");
+
+        for (h, line) in self.board.iter().enumerate() {
+            frame.push('|');
+            for (w, ch) in line.iter().enumerate() {
+                frame.push(match *ch {
+                    '.'                             => empty,
+                    c if highlight == Some((h, w))  => c.to_ascii_lowercase(),
+                    c                               => c,
+                });
             }
+            frame.push_str("|\n");
         }
-        println!("/");
+
+        frame.push('\\');
+        frame.push_str(&border);
+        frame.push_str("/\n");
+
+        frame
     }
 
     /// Inserts the letter of current player on to board, making sure it's
-    /// a valid position.
-    pub fn insert_move(&mut self, h: usize, w: usize, current_player: &Player) 
-        -> Result<(), Box<error::Error>> 
+    /// a valid position. MoveError::GroupTooBig if checking for suicide meets
+    /// a group bigger than the flood fill limit.
+    pub fn insert_move(&mut self, h: usize, w: usize, current_player: &Player)
+        -> Result<(), MoveError>
     {
-        if h >= self.height {
-            return Err(From::from("Invalid row"));
-        } else if w >= self.width {
-            return Err(From::from("Invalid column"));
+        match self.move_error(h, w, current_player) {
+            Ok(None)    => {},
+            Ok(Some(e)) => return Err(e),
+            Err(_)      => return Err(MoveError::GroupTooBig),
         }
-        
-        let player = match *current_player {
-            Player::O => 'O',
-            Player::X => 'X',
-        };
 
-        if self.board[h][w] == 'O' || self.board[h][w] == 'X' {
-            return Err(From::from("Position already taken"));
-        }
+        let player = player_char(current_player);
 
         self.board[h][w] = player;
+        self.history.push((h, w, player));
 
         Ok(())
     }
 
+    /// Cell of the last move made with insert_move that hasn't been undone.
+    pub fn last_move(&self) -> Option<(usize, usize)> {
+        self.history.last().map(|&(h, w, _)| (h, w))
+    }
+
+    /// Takes back the last move made with insert_move, emptying its cell.
+    /// Returns where it was, or None if there is nothing to take back.
+    pub fn undo(&mut self) -> Option<(usize, usize)> {
+        let (h, w, _) = self.history.pop()?;
+        self.board[h][w] = '.';
+
+        Some((h, w))
+    }
+
     /// Check if the game has been won or not.
     /// 
-    /// Return: 
-    ///     Some(usize, usize): If there was a winner function returns a tuple 
-    ///         containing the coordinates that a win was determined. These are 
-    ///         used to print the correct winning player (a player can place a
-    ///         losing piece).
-    ///         
-    ///     None: No win was found.
-    pub fn check_win(&mut self) -> Option<(usize, usize)> {
+    /// Return:
+    /// - Some(usize, usize): If there was a winner function returns a tuple
+    ///   containing the coordinates that a win was determined. These are
+    ///   used to print the correct winning player (a player can place a
+    ///   losing piece).
+    /// - None: No win was found.
+    ///
+    /// Scans the whole board. After a move use check_win_at, which only looks
+    /// at the groups the move touched.
+    pub fn check_win(&self) -> Option<(usize, usize)> {
         for h in 0..self.height {
             for w in 0..self.width {
                 if self.board[h][w] == '.' {
@@ -151,179 +504,1956 @@ impl GameBoard {
         None
     }
 
-    /// Checks if a piece has any liberties. Liberties are places a piece 
-    /// can grow in to ('.'s). Above, below, left, and right of a piece. Same 
-    /// pieces touching are linked. If one of them has a liberty they all have a liberty.
-    fn check_liberty(&mut self, h: usize, w: usize) -> bool {
-        let mut liberty = false;
-        // - 1 to set last_height and last_width to real end of vec.
-        let last_height = self.height - 1;
-        let last_width  = self.width - 1;
-        let player      = self.board[h][w];
-        let checked     = match player {
-            'O' => 'o',
-            'X' => 'x',
-             _  => '.',       // This should never happen.
-        };
+    /// Same as check_win but returns NogoError::Internal instead of walking a
+    /// group bigger than the flood fill limit. Use on boards from untrusted
+    /// files.
+    pub fn try_check_win(&self) -> Result<Option<(usize, usize)>, NogoError> {
+        for h in 0..self.height {
+            for w in 0..self.width {
+                if self.board[h][w] != '.' && !self.flood_liberty(h, w, None)? {
+                    return Ok(Some((h, w)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Same as check_win but only checks the groups a stone just placed at
+    /// (h, w) could have left without liberties: its own and the opponent
+    /// groups next to it. Returns the same cell check_win would if nothing
+    /// else on the board was already captured. None if (h, w) is off the
+    /// board.
+    pub fn check_win_at(&self, h: usize, w: usize) -> Option<(usize, usize)> {
+        if h >= self.height || w >= self.width {
+            return None;
+        }
+
+        match self.win_at(h, w, usize::MAX) {
+            Ok(dead) => dead,
+            Err(_)   => unreachable!("no group has more than usize::MAX stones"),
+        }
+    }
+
+    /// Same as check_win_at but returns NogoError::Internal instead of
+    /// walking a group bigger than the flood fill limit, or if (h, w) is off
+    /// the board.
+    pub fn try_check_win_at(&self, h: usize, w: usize) -> Result<Option<(usize, usize)>, NogoError> {
+        self.win_at(h, w, self.flood_limit)
+    }
 
-        // Just incase it does happen panic since it is undefined behaviour.
-        assert_ne!(checked, '.', "'checked' was equal to '.'");
+    /// First cell, scanning row by row, of a group touched by (h, w) with no
+    /// liberties. Gives up after visiting limit stones in one group.
+    fn win_at(&self, h: usize, w: usize, limit: usize) -> Result<Option<(usize, usize)>, NogoError> {
+        match self.get(h, w) {
+            Some('.')   => return Ok(None),
+            Some(_)     => {},
+            None        => return Err(NogoError::Internal(format!("win check at {} {} is off the board", h, w))),
+        }
 
-        // Check order is important.
-        //
-        // Check left.
-        if w != 0 {
-            let left = self.board[h][w - 1];
+        let mut dead = None;
+        let touched  = self.neighbors(h, w).filter(|&(nh, nw)| self.board[nh][nw] != '.');
 
-            if left == '.' {
-                return true;
-            } else if left == player {
-                // Recursively check all linked pieces. Piece is changed if it 
-                // has been checked. Each piece returned to normal as function "unwinds".
-                self.board[h][w] = checked;
-                liberty = self.check_liberty(h, w - 1);
-                self.board[h][w] = player;
+        for (gh, gw) in Some((h, w)).into_iter().chain(touched) {
+            if !self.flood(gh, gw, None, limit)? {
+                let first = self.get_group(gh, gw).into_iter().min();
+                dead = dead.into_iter().chain(first).min();
             }
         }
 
-        // Check top.
-        if h != 0 {
-            let top = self.board[h - 1][w];
+        Ok(dead)
+    }
+
+    /// Hash of the board's dimensions and cells. Equal positions always hash
+    /// the same, whatever moves led to them.
+    pub fn position_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.height.hash(&mut hasher);
+        self.width.hash(&mut hasher);
+        self.board.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// Returns true if some group has no liberties. Results are remembered in
+    /// cache by position_hash so searches that reach the same position again
+    /// don't repeat the flood fill.
+    pub fn is_decided_cached(&self, cache: &mut DecidedCache) -> Result<bool, NogoError> {
+        let hash = self.position_hash();
+
+        if let Some(&decided) = cache.decided.get(&hash) {
+            return Ok(decided);
+        }
+
+        let decided = self.try_check_win()?.is_some();
+        cache.decided.insert(hash, decided);
+
+        Ok(decided)
+    }
+
+    /// Returns true if placing the player's stone on the empty cell (h, w)
+    /// would leave its group without liberties while not capturing any of
+    /// the opponent's groups. NogoError::Internal if a neighbouring group is
+    /// bigger than the flood fill limit.
+    pub fn would_self_capture(&self, h: usize, w: usize, player: &Player) -> Result<bool, NogoError> {
+        let stone = player_char(player);
+
+        for (nh, nw) in self.neighbors(h, w) {
+            let ch = self.board[nh][nw];
+
+            if ch == '.' {
+                return Ok(false);
+            }
 
-            if top == '.' && !liberty {
-                return true;
-            } else if top == player && !liberty {
-                self.board[h][w] = checked;
-                liberty = self.check_liberty(h - 1, w);
-                self.board[h][w] = player;
+            // Joins a group that has another liberty, or takes the last
+            // liberty of an opponent's group.
+            let has_liberty = self.has_liberty_except(nh, nw, (h, w))?;
+            if (ch == stone) == has_liberty {
+                return Ok(false);
             }
         }
 
-        // Check right.
-        if w != last_width {
-            let right = self.board[h][w + 1];
+        Ok(true)
+    }
+
+    /// Returns true if the player placing a stone at (h, w) would leave its
+    /// own group without liberties while capturing nothing. False for taken
+    /// cells and cells off the board. NogoError::Internal if a touching
+    /// group is bigger than the flood fill limit.
+    pub fn is_suicide(&self, h: usize, w: usize, player: &Player) -> Result<bool, NogoError> {
+        Ok(self.get(h, w) == Some('.') && self.would_self_capture(h, w, player)?)
+    }
+
+    /// Returns true if there are no empty cells left.
+    pub fn is_full(&self) -> bool {
+        self.board.iter().all(|line| line.iter().all(|ch| *ch != '.'))
+    }
+
+    /// Number of player's stones on the board.
+    pub fn stone_count(&self, player: &Player) -> usize {
+        let stone = player_char(player);
+        self.cells().filter(|&(_, _, ch)| ch == stone).count()
+    }
+
+    /// Number of empty cells on the board.
+    pub fn empty_count(&self) -> usize {
+        self.cells().filter(|&(_, _, ch)| ch == '.').count()
+    }
+
+    /// Number of empty cells the player can place on without capturing
+    /// their own group.
+    pub fn mobility(&self, player: &Player) -> Result<usize, NogoError> {
+        let mut count = 0;
 
-            if right == '.' && !liberty {
-                return true;
-            } else if right == player && !liberty {
-                self.board[h][w] = checked;
-                liberty = self.check_liberty(h, w + 1);
-                self.board[h][w] = player;
+        for h in 0..self.height {
+            for w in 0..self.width {
+                if self.board[h][w] == '.' && !self.would_self_capture(h, w, player)? {
+                    count += 1;
+                }
             }
         }
 
-        // Check bottom.
-        if h != last_height {
-            let bottom = self.board[h + 1][w];
+        Ok(count)
+    }
+
+    /// O's mobility minus X's. Positive when O has more safe moves.
+    pub fn mobility_balance(&self) -> Result<i64, NogoError> {
+        Ok(self.mobility(&Player::O)? as i64 - self.mobility(&Player::X)? as i64)
+    }
+
+    /// Phase of the game. Under MIDDLEGAME_PERCENT of the board filled is the
+    /// opening and over ENDGAME_PERCENT is the endgame.
+    pub fn phase(&self) -> Phase {
+        let filled = self.board.iter()
+                               .flat_map(|line| line.iter())
+                               .filter(|ch| **ch != '.')
+                               .count() * 100;
+        let cells  = self.height * self.width;
+
+        if filled < MIDDLEGAME_PERCENT * cells {
+            Phase::Opening
+        } else if filled <= ENDGAME_PERCENT * cells {
+            Phase::Middlegame
+        } else {
+            Phase::Endgame
+        }
+    }
+
+    /// Empty cells where the player can play without capturing or putting in
+    /// atari any opponent group touching the cell. Groups already in atari
+    /// elsewhere are left out of the check since the move doesn't change them.
+    pub fn keeps_opponent_safe_moves(&self, player: &Player) -> Vec<(usize, usize)> {
+        let mut moves = Vec::new();
+
+        for h in 0..self.height {
+            for w in 0..self.width {
+                if self.board[h][w] != '.' {
+                    continue;
+                }
 
-            if bottom == '.' && !liberty {
-                return true;
-            } else if bottom == player && !liberty {
-                self.board[h][w] = checked;
-                liberty = self.check_liberty(h + 1, w);
-                self.board[h][w] = player;
+                if self.is_quiet_move(h, w, player) {
+                    moves.push((h, w));
+                }
             }
         }
 
-        liberty
+        moves
+    }
+
+    /// Whether (h, w) is a corner, on an edge, or in the interior of the
+    /// board. The cell must be on the board.
+    pub fn cell_position(&self, h: usize, w: usize) -> CellKind {
+        let top_or_bottom = h == 0 || h == self.height - 1;
+        let left_or_right = w == 0 || w == self.width - 1;
+
+        match (top_or_bottom, left_or_right) {
+            (true, true)    => CellKind::Corner,
+            (false, false)  => CellKind::Interior,
+            _               => CellKind::Edge,
+        }
+    }
+
+    /// Returns true if the player's stone on the empty cell (h, w) leaves
+    /// every opponent group touching it with at least two liberties.
+    pub fn is_quiet_move(&self, h: usize, w: usize, player: &Player) -> bool {
+        let opponent = if *player == Player::O { 'X' } else { 'O' };
+
+        // (h, w) is one of each touching group's liberties, so the group
+        // keeps two only if it has three now.
+        self.neighbors(h, w)
+            .filter(|&(nh, nw)| self.board[nh][nw] == opponent)
+            .all(|(nh, nw)| self.count_liberties(nh, nw) >= 3)
     }
 
-    /// Appends to file the board with no borders.
-    pub fn save(&self, filename: &str) -> Result<(), Box<error::Error>> {
-        use std::fs::OpenOptions;
-        use std::io::Write;
+    /// Returns true if the player's stone on the empty cell (h, w) would
+    /// leave a touching opponent group with exactly one liberty.
+    pub fn puts_in_atari(&self, h: usize, w: usize, player: &Player) -> bool {
+        let opponent = if *player == Player::O { 'X' } else { 'O' };
 
-        let mut file = OpenOptions::new().append(true).open(filename)?;
+        // (h, w) is one of each touching group's liberties.
+        self.neighbors(h, w)
+            .filter(|&(nh, nw)| self.board[nh][nw] == opponent)
+            .any(|(nh, nw)| self.count_liberties(nh, nw) == 2)
+    }
 
-        for line in &self.board {
-            for ch in line {
-                write!(file, "{}", ch)?;
+    /// Returns true if the player's stone on the empty cell (h, w) would
+    /// leave any group without liberties, its own or the opponent's, losing
+    /// the game. NogoError::Internal if a touching group is bigger than the
+    /// flood fill limit.
+    pub fn is_losing_move(&self, h: usize, w: usize, player: &Player) -> Result<bool, NogoError> {
+        let stone           = player_char(player);
+        let mut has_liberty = false;
+
+        for (nh, nw) in self.neighbors(h, w) {
+            let ch = self.board[nh][nw];
+
+            if ch == '.' {
+                has_liberty = true;
+            } else if ch == stone {
+                has_liberty |= self.has_liberty_except(nh, nw, (h, w))?;
+            } else if !self.has_liberty_except(nh, nw, (h, w))? {
+                return Ok(true);
             }
-            writeln!(file)?;
         }
 
-        Ok(())
+        Ok(!has_liberty)
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    /// Every group on the board with its stones and number of liberties, in
+    /// the order their first stone appears scanning row by row.
+    pub fn analyze_groups(&self) -> Vec<GroupInfo> {
+        let mut seen   = vec![vec![false; self.width]; self.height];
+        let mut groups = Vec::new();
 
-    #[test]
-    fn test_win() {
-        let mut game = GameBoard::new(6, 5).unwrap();
-        let vec = vec![     
-                     //   0    1    2    3    4
-            /* 0 */ vec!['X', 'X', '.', 'X', 'X'],
-            /* 1 */ vec!['X', 'X', 'O', 'X', 'X'],
-            /* 2 */ vec!['O', 'O', 'O', 'O', 'O'],
-            /* 3 */ vec!['X', 'X', 'O', 'X', 'X'],
-            /* 4 */ vec!['X', 'X', 'O', 'X', 'X'],
-            /* 5 */ vec!['X', 'X', '.', 'X', 'X']
-        ];
+        for h in 0..self.height {
+            for w in 0..self.width {
+                if self.board[h][w] == '.' || seen[h][w] {
+                    continue;
+                }
 
-        game.board = vec.clone();
-        assert_eq!(game.board, vec);
+                let cells = self.get_group(h, w);
+                for &(gh, gw) in &cells {
+                    seen[gh][gw] = true;
+                }
 
-        // Player O
-        //assert_eq!(game.check_liberty(0, 2), true);
-        assert_eq!(game.check_liberty(1, 2), true);
-        assert_eq!(game.check_liberty(2, 0), true);
-        assert_eq!(game.check_liberty(2, 1), true);
-        assert_eq!(game.check_liberty(2, 2), true);
-        assert_eq!(game.check_liberty(2, 3), true);
-        assert_eq!(game.check_liberty(2, 4), true);
-        assert_eq!(game.check_liberty(3, 2), true);
-        assert_eq!(game.check_liberty(4, 2), true);
-        //assert_eq!(game.check_liberty(5, 2), true);
+                groups.push(GroupInfo {
+                    color:      if self.board[h][w] == 'O' { Player::O } else { Player::X },
+                    liberties:  self.liberties_of(&cells),
+                    cells,
+                });
+            }
+        }
 
-        // Player X
-        assert_eq!(game.check_liberty(0, 1), true);
-        assert_eq!(game.check_liberty(0, 3), true);
-        assert_eq!(game.check_liberty(1, 0), true);
-        assert_eq!(game.check_liberty(1, 1), true);
-        assert_eq!(game.check_liberty(1, 3), true);
-        assert_eq!(game.check_liberty(1, 4), true);
-        assert_eq!(game.check_liberty(3, 0), true);
-        assert_eq!(game.check_liberty(3, 1), true);
-        assert_eq!(game.check_liberty(3, 3), true);
-        assert_eq!(game.check_liberty(3, 4), true);
-        assert_eq!(game.check_liberty(4, 1), true);
-        assert_eq!(game.check_liberty(4, 3), true);
-        assert_eq!(game.check_liberty(5, 1), true);
-        assert_eq!(game.check_liberty(5, 3), true);
+        groups
     }
 
-    #[test]
-    fn test_insert() {
-        let mut game = GameBoard::new(6, 5).unwrap();
-        let vec = vec![     
-                     //   0    1    2    3    4
-            /* 0 */ vec!['X', '.', 'O', '.', 'O'],
-            /* 1 */ vec!['.', 'O', '.', 'X', '.'],
-            /* 2 */ vec!['.', '.', 'X', '.', '.'],
-            /* 3 */ vec!['.', 'X', '.', 'O', '.'],
-            /* 4 */ vec!['O', '.', 'X', '.', 'X'],
-            /* 5 */ vec!['O', '.', 'X', '.', 'X']
-        ];
+    /// Liberties of the group at a minus liberties of the group at b. Positive
+    /// when a's group is ahead in a race to capture the other. Returns 0 if
+    /// either cell is empty or off the board.
+    pub fn capture_race(&self, a: (usize, usize), b: (usize, usize)) -> i64 {
+        let is_stone = |(h, w)| self.get(h, w).is_some_and(|ch| ch != '.');
+        if !is_stone(a) || !is_stone(b) {
+            return 0;
+        }
 
-        game.insert_move(0, 0, &Player::X);
-        game.insert_move(1, 3, &Player::X);
-        game.insert_move(2, 2, &Player::X);
-        game.insert_move(3, 1, &Player::X);
-        game.insert_move(4, 2, &Player::X);
-        game.insert_move(4, 4, &Player::X);
-        game.insert_move(5, 2, &Player::X);
-        game.insert_move(5, 4, &Player::X);
-
-        game.insert_move(0, 2, &Player::O);
-        game.insert_move(0, 4, &Player::O);
-        game.insert_move(1, 1, &Player::O);
-        game.insert_move(3, 3, &Player::O);
-        game.insert_move(4, 0, &Player::O);
-        game.insert_move(5, 0, &Player::O);
+        let a_liberties = self.count_liberties(a.0, a.1);
+        let b_liberties = self.count_liberties(b.0, b.1);
 
-        assert_eq!(game.board, vec);
+        a_liberties as i64 - b_liberties as i64
+    }
+
+    /// Looks for a captured group that last_player's final move couldn't have
+    /// made, meaning the game should have ended earlier. Returns a stone of
+    /// the first such group. NogoError::Internal if a group is bigger than the
+    /// flood fill limit.
+    pub fn early_capture(&self, last_player: &Player) -> Result<Option<(usize, usize)>, NogoError> {
+        let stone    = player_char(last_player);
+        let mut seen = vec![vec![false; self.width]; self.height];
+        let mut dead = Vec::new();
+
+        for h in 0..self.height {
+            for w in 0..self.width {
+                if self.board[h][w] == '.' || seen[h][w] {
+                    continue;
+                }
+
+                let group = self.get_group(h, w);
+                for &(gh, gw) in &group {
+                    seen[gh][gw] = true;
+                }
+
+                if !self.flood_liberty(h, w, None)? {
+                    dead.push(group);
+                }
+            }
+        }
+
+        if dead.is_empty() {
+            return Ok(None);
+        }
+
+        // The last move either filled its own group's last liberty or took
+        // the last liberty of the opponent's groups next to it.
+        let explains = |last: (usize, usize)| {
+            dead.iter().all(|group| {
+                let (gh, gw) = group[0];
+                if self.board[gh][gw] == stone {
+                    group.contains(&last)
+                } else {
+                    group.iter().any(|&(h, w)| self.neighbors(h, w).any(|n| n == last))
+                }
+            })
+        };
+
+        for h in 0..self.height {
+            for w in 0..self.width {
+                if self.board[h][w] == stone && explains((h, w)) {
+                    return Ok(None);
+                }
+            }
+        }
+
+        Ok(Some(dead[0][0]))
+    }
+
+    /// Returns true if the position could come from a real game: O moves
+    /// first and players alternate, so O has the same number of stones as X
+    /// or one more, and any captured group was captured by the last move.
+    /// Doesn't check the stones could have been played in some order.
+    /// NogoError::Internal if a group is bigger than the flood fill limit.
+    pub fn is_reachable(&self) -> Result<bool, NogoError> {
+        let cells = self.board.iter().flat_map(|line| line.iter());
+        let o     = cells.clone().filter(|ch| **ch == 'O').count();
+        let x     = cells.filter(|ch| **ch == 'X').count();
+
+        let last_player = if o == x + 1 {
+            Player::O
+        } else if o == x {
+            Player::X
+        } else {
+            return Ok(false);
+        };
+
+        Ok(self.early_capture(&last_player)?.is_none())
+    }
+
+    /// Returns true if any of the player's groups has no liberties, meaning
+    /// they have already lost.
+    pub fn is_lost(&self, player: &Player) -> bool {
+        let stone = player_char(player);
+
+        for h in 0..self.height {
+            for w in 0..self.width {
+                if self.board[h][w] == stone && !self.check_liberty(h, w) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Every stone in the group at (h, w), starting with (h, w). Each stone
+    /// appears once. Empty if (h, w) is empty or off the board.
+    pub fn get_group(&self, h: usize, w: usize) -> Vec<(usize, usize)> {
+        if h >= self.height || w >= self.width {
+            return Vec::new();
+        }
+
+        let stone = self.board[h][w];
+        if stone == '.' {
+            return Vec::new();
+        }
+
+        let mut group = vec![(h, w)];
+        let mut seen  = HashSet::new();
+        seen.insert((h, w));
+
+        let mut next = 0;
+        while next < group.len() {
+            let (gh, gw) = group[next];
+            next += 1;
+
+            for (nh, nw) in self.neighbors(gh, gw) {
+                if self.board[nh][nw] == stone && seen.insert((nh, nw)) {
+                    group.push((nh, nw));
+                }
+            }
+        }
+
+        group
+    }
+
+    /// Number of distinct empty cells next to the group at (h, w). 0 if (h, w)
+    /// is empty.
+    pub fn count_liberties(&self, h: usize, w: usize) -> usize {
+        self.liberties_of(&self.get_group(h, w))
+    }
+
+    /// Number of distinct empty cells next to the given stones.
+    fn liberties_of(&self, cells: &[(usize, usize)]) -> usize {
+        let mut liberties = HashSet::new();
+
+        for &(h, w) in cells {
+            for (nh, nw) in self.neighbors(h, w) {
+                if self.board[nh][nw] == '.' {
+                    liberties.insert((nh, nw));
+                }
+            }
+        }
+
+        liberties.len()
+    }
+
+    /// Returns true if the group at (h, w) has a liberty that isn't ignore.
+    fn has_liberty_except(&self, h: usize, w: usize, ignore: (usize, usize)) -> Result<bool, NogoError> {
+        self.flood_liberty(h, w, Some(ignore))
+    }
+
+    /// Walks the group at (h, w) looking for a liberty that isn't ignore.
+    /// Gives up with NogoError::Internal after visiting flood_limit stones.
+    fn flood_liberty(&self, h: usize, w: usize, ignore: Option<(usize, usize)>)
+        -> Result<bool, NogoError>
+    {
+        self.flood(h, w, ignore, self.flood_limit)
+    }
+
+    /// Same as flood_liberty but gives up after visiting limit stones.
+    fn flood(&self, h: usize, w: usize, ignore: Option<(usize, usize)>, limit: usize)
+        -> Result<bool, NogoError>
+    {
+        let stone       = self.board[h][w];
+        let mut visited = HashSet::new();
+        let mut stack   = vec![(h, w)];
+        visited.insert((h, w));
+
+        while let Some((sh, sw)) = stack.pop() {
+            for (nh, nw) in self.neighbors(sh, sw) {
+                let ch = self.board[nh][nw];
+
+                if ch == '.' && Some((nh, nw)) != ignore {
+                    return Ok(true);
+                } else if ch == stone && visited.insert((nh, nw)) {
+                    if visited.len() > limit {
+                        return Err(NogoError::Internal(format!(
+                            "group at ({}, {}) has more than {} stones", h, w, limit)));
+                    }
+                    stack.push((nh, nw));
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Coordinates left, above, right, and below (h, w) that are on the board.
+    fn neighbors(&self, h: usize, w: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (height, width) = (self.height, self.width);
+        Direction::ALL.iter().filter_map(move |d| d.step(h, w, height, width))
+    }
+
+    /// Checks if a piece has any liberties. Liberties are places a piece 
+    /// can grow in to ('.'s). Above, below, left, and right of a piece. Same 
+    /// pieces touching are linked. If one of them has a liberty they all have a liberty.
+    fn check_liberty(&self, h: usize, w: usize) -> bool {
+        // Walks the group with a stack rather than recursing so a group
+        // snaking across a 1000x1000 board can't overflow the call stack.
+        match self.flood(h, w, None, usize::MAX) {
+            Ok(liberty) => liberty,
+            Err(_)      => unreachable!("no group has more than usize::MAX stones"),
+        }
+    }
+
+    /// Marks every empty cell that is a liberty of some group, that is, it is
+    /// directly above, below, left, or right of a stone. Empty cells that
+    /// don't touch any stone are false.
+    pub fn all_liberties(&self) -> Vec<Vec<bool>> {
+        let mut liberties = vec![vec![false; self.width]; self.height];
+
+        for (h, row) in liberties.iter_mut().enumerate() {
+            for (w, liberty) in row.iter_mut().enumerate() {
+                if self.board[h][w] != '.' {
+                    continue;
+                }
+
+                *liberty = self.neighbors(h, w).any(|(nh, nw)| self.board[nh][nw] != '.');
+            }
+        }
+
+        liberties
+    }
+
+    /// Returns the average row and column of the player's stones, or None if
+    /// they don't have any.
+    pub fn centroid(&self, player: &Player) -> Option<(f64, f64)> {
+        let stone = player_char(player);
+        let mut count   = 0;
+        let mut row_sum = 0;
+        let mut col_sum = 0;
+
+        for (h, line) in self.board.iter().enumerate() {
+            for (w, ch) in line.iter().enumerate() {
+                if *ch == stone {
+                    count   += 1;
+                    row_sum += h;
+                    col_sum += w;
+                }
+            }
+        }
+
+        if count == 0 {
+            return None;
+        }
+
+        Some((row_sum as f64 / count as f64, col_sum as f64 / count as f64))
+    }
+
+    /// Encodes the board and the player to move next as a single URL safe
+    /// line, for example "4x5-X-.O.........X......".
+    pub fn to_puzzle_string(&self, next: &Player) -> String {
+        let cells: String = self.board.iter().flat_map(|line| line.iter()).collect();
+
+        format!("{}x{}-{}-{}", self.height, self.width, next, cells)
+    }
+
+    /// Decodes a line made by to_puzzle_string. Returns the board and the
+    /// player to move next.
+    pub fn from_puzzle_string(puzzle: &str) -> Result<(GameBoard, Player), NogoError> {
+        let mut parts = puzzle.trim().split('-');
+        let mut dimensions = parts.next().ok_or(NogoError::corrupt(0, "missing dimensions"))?.split('x');
+        let height: usize = dimensions.next().ok_or(NogoError::corrupt(0, "missing height"))?.parse()?;
+        let width: usize  = dimensions.next().ok_or(NogoError::corrupt(0, "missing width"))?.parse()?;
+
+        let next = match parts.next() {
+            Some("O") => Player::O,
+            Some("X") => Player::X,
+            _         => return Err(NogoError::corrupt(0, "next player must be O or X")),
+        };
+
+        let cells = parts.next().ok_or(NogoError::corrupt(0, "missing cells"))?;
+        if dimensions.next().is_some() || parts.next().is_some() {
+            return Err(NogoError::corrupt(0, "too many parts"));
+        }
+
+        Ok((GameBoard::from_flat(height, width, cells)?, next))
+    }
+
+    /// Reads a board from an SGF record: its size, AB and AW setup stones,
+    /// then B and W moves in order. O plays black and X plays white, the same
+    /// as to_sgf. Other properties are ignored.
+    pub fn from_sgf(sgf: &str) -> Result<GameBoard, NogoError> {
+        let properties = sgf_properties(sgf)?;
+
+        // SGF boards are 19x19 unless they say otherwise.
+        let size = properties.iter().find(|p| p.0 == "SZ").and_then(|p| p.1.first());
+        let (width, height) = match size {
+            Some(size) => {
+                let bad_size = || NogoError::corrupt(0, format!("bad board size \"{}\"", size));
+                let mut parts = size.split(':').map(|n| n.trim().parse::<usize>().map_err(|_| bad_size()));
+                let width     = parts.next().ok_or_else(bad_size)??;
+                let height    = parts.next().unwrap_or(Ok(width))?;
+                if parts.next().is_some() {
+                    return Err(bad_size());
+                }
+                (width, height)
+            },
+            None       => (19, 19),
+        };
+
+        let mut board = GameBoard::new(height, width)?;
+
+        for (name, values) in &properties {
+            let player = match &name[..] {
+                "AB" | "B" => Player::O,
+                "AW" | "W" => Player::X,
+                _          => continue,
+            };
+
+            for value in values {
+                // An empty move is a pass.
+                if value.is_empty() && name.len() == 1 {
+                    continue;
+                }
+
+                let point = value.chars().map(sgf_index).collect::<Vec<Option<usize>>>();
+                let (h, w) = match point[..] {
+                    [Some(w), Some(h)] if h < height && w < width => (h, w),
+                    _ => return Err(NogoError::corrupt(0, format!("{}[{}] isn't on the board", name, value))),
+                };
+
+                if name.len() == 1 {
+                    board.insert_move(h, w, &player)
+                         .map_err(|e| NogoError::corrupt(0, format!("{}[{}]: {}", name, value, e)))?;
+                } else if board.board[h][w] == '.' {
+                    board.board[h][w] = player_char(&player);
+                } else {
+                    return Err(NogoError::corrupt(0, format!("{}[{}] is already taken", name, value)));
+                }
+            }
+        }
+
+        Ok(board)
+    }
+
+    /// Returns the game as an SGF record. O plays black and X plays white.
+    /// Stones that weren't placed with insert_move (a loaded board) are added
+    /// as setup stones. InvalidDimension if a side is longer than
+    /// SGF_MAX_DIMENSION, since SGF coordinates can't reach past it.
+    pub fn to_sgf(&self, result: Option<&GameResult>) -> Result<String, NogoError> {
+        if self.height > SGF_MAX_DIMENSION || self.width > SGF_MAX_DIMENSION {
+            return Err(NogoError::InvalidDimension);
+        }
+
+        let mut sgf = if self.height == self.width {
+            format!("(;FF[4]GM[1]SZ[{}]", self.width)
+        } else {
+            format!("(;FF[4]GM[1]SZ[{}:{}]", self.width, self.height)
+        };
+
+        if let Some(r) = result {
+            sgf.push_str(&format!("RE[{}]", r.to_sgf()));
+        }
+
+        let played = self.history.iter().map(|&(h, w, _)| (h, w)).collect::<HashSet<(usize, usize)>>();
+
+        let mut black = String::new();
+        let mut white = String::new();
+        for h in 0..self.height {
+            for w in 0..self.width {
+                let ch = self.board[h][w];
+                if ch == '.' || played.contains(&(h, w)) {
+                    continue;
+                }
+
+                let point = format!("[{}{}]", sgf_coord(w), sgf_coord(h));
+                match ch {
+                    'O' => black.push_str(&point),
+                    _   => white.push_str(&point),
+                }
+            }
+        }
+
+        if !black.is_empty() {
+            sgf.push_str("AB");
+            sgf.push_str(&black);
+        }
+        if !white.is_empty() {
+            sgf.push_str("AW");
+            sgf.push_str(&white);
+        }
+
+        for &(h, w, ch) in &self.history {
+            let color = match ch {
+                'O' => 'B',
+                 _  => 'W',
+            };
+            sgf.push_str(&format!(";{}[{}{}]", color, sgf_coord(w), sgf_coord(h)));
+        }
+
+        sgf.push(')');
+
+        Ok(sgf)
+    }
+
+    /// Writes the board with no borders to w, after whatever has already
+    /// been written to it.
+    pub fn save<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        for line in &self.board {
+            let row: String = line.iter().collect();
+            writeln!(w, "{}", row)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns true if n is a usable height or width.
+pub fn is_dimension(n: usize) -> bool {
+    (MIN_DIMENSION..=MAX_DIMENSION).contains(&n)
+}
+
+/// Checks a board can be height by width. The error says why not.
+fn check_dimensions(height: usize, width: usize) -> Result<(), String> {
+    if is_dimension(height) && is_dimension(width) {
+        return Ok(());
+    }
+
+    Err(format!("board is {}x{} but sides must be {} to {}", height, width, MIN_DIMENSION, MAX_DIMENSION))
+}
+
+/// Returns true if ch can be on a board: empty, O, or X.
+fn is_cell(ch: char) -> bool {
+    ch == '.' || ch == 'O' || ch == 'X'
+}
+
+/// Returns the character a player's stones are shown as on the board.
+fn player_char(player: &Player) -> char {
+    match *player {
+        Player::O => 'O',
+        Player::X => 'X',
+    }
+}
+
+/// Splits an SGF record into its properties in order, each with its values.
+/// Nodes and variations aren't kept apart.
+fn sgf_properties(sgf: &str) -> Result<Vec<(String, Vec<String>)>, NogoError> {
+    if !sgf.trim_start().starts_with('(') {
+        return Err(NogoError::corrupt(0, "SGF must start with '('"));
+    }
+
+    let mut properties = Vec::new();
+    let mut chars      = sgf.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '(' | ')' | ';'             => continue,
+            c if c.is_whitespace()      => continue,
+            c if c.is_ascii_uppercase() => {
+                let mut name = c.to_string();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                    // Old SGF allows lower case letters in names, which are
+                    // dropped.
+                    if c.is_ascii_uppercase() {
+                        name.push(c);
+                    }
+                    chars.next();
+                }
+
+                let mut values = Vec::new();
+                loop {
+                    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                        chars.next();
+                    }
+                    if chars.peek() != Some(&'[') {
+                        break;
+                    }
+                    chars.next();
+
+                    let mut value = String::new();
+                    loop {
+                        match chars.next() {
+                            Some(']')  => break,
+                            Some('\\') => value.extend(chars.next()),
+                            Some(c)    => value.push(c),
+                            None       => return Err(NogoError::corrupt(0, format!("{} value isn't closed", name))),
+                        }
+                    }
+                    values.push(value);
+                }
+
+                if values.is_empty() {
+                    return Err(NogoError::corrupt(0, format!("{} has no value", name)));
+                }
+                properties.push((name, values));
+            },
+            c                           => return Err(NogoError::corrupt(0, format!("unexpected '{}' in SGF", c))),
+        }
+    }
+
+    Ok(properties)
+}
+
+/// Converts an SGF coordinate letter back to a board index.
+fn sgf_index(ch: char) -> Option<usize> {
+    match ch {
+        'a'..='z' => Some(ch as usize - 'a' as usize),
+        'A'..='Z' => Some(ch as usize - 'A' as usize + 26),
+        _         => None,
+    }
+}
+
+/// Converts a board index to an SGF coordinate letter.
+fn sgf_coord(n: usize) -> char {
+    match n {
+        0..=25  => (b'a' + n as u8) as char,
+        26..=51 => (b'A' + (n - 26) as u8) as char,
+        _       => unreachable!("to_sgf checks boards fit SGF_MAX_DIMENSION"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_win() {
+        let mut game = GameBoard::new(6, 5).unwrap();
+        let vec = vec![     
+                     //   0    1    2    3    4
+            /* 0 */ vec!['X', 'X', '.', 'X', 'X'],
+            /* 1 */ vec!['X', 'X', 'O', 'X', 'X'],
+            /* 2 */ vec!['O', 'O', 'O', 'O', 'O'],
+            /* 3 */ vec!['X', 'X', 'O', 'X', 'X'],
+            /* 4 */ vec!['X', 'X', 'O', 'X', 'X'],
+            /* 5 */ vec!['X', 'X', '.', 'X', 'X']
+        ];
+
+        game.board = vec.clone();
+        assert_eq!(game.board, vec);
+
+        // Player O
+        //assert!(game.check_liberty(0, 2));
+        assert!(game.check_liberty(1, 2));
+        assert!(game.check_liberty(2, 0));
+        assert!(game.check_liberty(2, 1));
+        assert!(game.check_liberty(2, 2));
+        assert!(game.check_liberty(2, 3));
+        assert!(game.check_liberty(2, 4));
+        assert!(game.check_liberty(3, 2));
+        assert!(game.check_liberty(4, 2));
+        //assert!(game.check_liberty(5, 2));
+
+        // Player X
+        assert!(game.check_liberty(0, 1));
+        assert!(game.check_liberty(0, 3));
+        assert!(game.check_liberty(1, 0));
+        assert!(game.check_liberty(1, 1));
+        assert!(game.check_liberty(1, 3));
+        assert!(game.check_liberty(1, 4));
+        assert!(game.check_liberty(3, 0));
+        assert!(game.check_liberty(3, 1));
+        assert!(game.check_liberty(3, 3));
+        assert!(game.check_liberty(3, 4));
+        assert!(game.check_liberty(4, 1));
+        assert!(game.check_liberty(4, 3));
+        assert!(game.check_liberty(5, 1));
+        assert!(game.check_liberty(5, 3));
+    }
+
+    #[test]
+    fn test_analyze_groups() {
+        let mut game = GameBoard::new(6, 5).unwrap();
+        game.board = vec![
+                     //   0    1    2    3    4
+            /* 0 */ vec!['X', 'X', '.', 'X', 'X'],
+            /* 1 */ vec!['X', 'X', 'O', 'X', 'X'],
+            /* 2 */ vec!['O', 'O', 'O', 'O', 'O'],
+            /* 3 */ vec!['X', 'X', 'O', 'X', 'X'],
+            /* 4 */ vec!['X', 'X', 'O', 'X', 'X'],
+            /* 5 */ vec!['X', 'X', '.', 'X', 'X']
+        ];
+
+        let groups = game.analyze_groups();
+        let summary: Vec<(Player, usize, usize)> = groups.iter()
+            .map(|g| (g.color, g.cells[0].0 * 5 + g.cells[0].1, g.liberties))
+            .collect();
+
+        assert_eq!(vec![(Player::X, 0,  1),
+                        (Player::X, 3,  1),
+                        (Player::O, 7,  2),
+                        (Player::X, 15, 1),
+                        (Player::X, 18, 1)], summary);
+
+        assert_eq!(4, groups[0].cells.len());
+        assert_eq!(8, groups[2].cells.len());
+        assert_eq!(6, groups[3].cells.len());
+        assert_eq!(30 - 2, groups.iter().map(|g| g.cells.len()).sum::<usize>());
+    }
+
+    #[test]
+    fn test_check_liberty_snake() {
+        // O rows joined at alternating ends by one O in each X row make a
+        // single group of about 500,000 stones.
+        let mut game = GameBoard::new(1000, 1000).unwrap();
+        for h in 0..1000 {
+            for w in 0..1000 {
+                game.board[h][w] = if h % 2 == 0 { 'O' } else { 'X' };
+            }
+            if h % 2 == 1 {
+                game.board[h][if h % 4 == 1 { 999 } else { 0 }] = 'O';
+            }
+        }
+
+        let before = game.board.clone();
+        assert!(!game.check_liberty(0, 0));
+        assert_eq!(before, game.board);
+
+        // Open one cell next to the snake's last row.
+        game.board[999][500] = '.';
+        assert!(game.check_liberty(0, 0));
+        assert!(!game.check_liberty(1, 0));
+    }
+
+    #[test]
+    fn test_get_group() {
+        let game = GameBoard::from("XX.XX\n\
+                                    XXOXX\n\
+                                    OOOOO\n\
+                                    XXOXX\n\
+                                    XXOXX\n\
+                                    XX.XX\n").unwrap();
+
+        let mut group = game.get_group(2, 2);
+        assert_eq!((2, 2), group[0]);
+        group.sort();
+        assert_eq!(vec![(1, 2), (2, 0), (2, 1), (2, 2), (2, 3), (2, 4), (3, 2), (4, 2)], group);
+
+        let mut corner = game.get_group(5, 4);
+        corner.sort();
+        assert_eq!(vec![(3, 3), (3, 4), (4, 3), (4, 4), (5, 3), (5, 4)], corner);
+
+        assert!(game.get_group(0, 2).is_empty());
+        assert!(game.get_group(6, 0).is_empty());
+        assert!(game.get_group(0, 5).is_empty());
+        assert!(game.get_group(usize::MAX, usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_count_liberties() {
+        let mut game = GameBoard::new(6, 5).unwrap();
+        game.board = vec![
+                     //   0    1    2    3    4
+            /* 0 */ vec!['X', 'X', '.', 'X', 'X'],
+            /* 1 */ vec!['X', 'X', 'O', 'X', 'X'],
+            /* 2 */ vec!['O', 'O', 'O', 'O', 'O'],
+            /* 3 */ vec!['X', 'X', 'O', 'X', 'X'],
+            /* 4 */ vec!['X', 'X', 'O', 'X', 'X'],
+            /* 5 */ vec!['X', 'X', '.', 'X', 'X']
+        ];
+
+        // Both ends of the O cross.
+        assert_eq!(2, game.count_liberties(2, 2));
+        assert_eq!(2, game.count_liberties(2, 0));
+
+        // Each X corner shares one end with the O cross.
+        assert_eq!(1, game.count_liberties(0, 0));
+        assert_eq!(1, game.count_liberties(1, 4));
+        assert_eq!(1, game.count_liberties(3, 0));
+        assert_eq!(1, game.count_liberties(5, 4));
+
+        assert_eq!(0, game.count_liberties(0, 2));
+
+        // (0, 1) is next to both (0, 0) and (1, 1) but counts once.
+        let game = GameBoard::from("O...\n\
+                                    OO..\n\
+                                    ....\n\
+                                    ....\n").unwrap();
+        assert_eq!(4, game.count_liberties(0, 0));
+    }
+
+    #[test]
+    fn test_check_win_at() {
+        let mut game = GameBoard::from("XO...\n\
+                                        .....\n\
+                                        ..XO.\n\
+                                        ..O..\n").unwrap();
+        assert_eq!(None, game.check_win_at(0, 1));
+
+        // O takes the corner X's last liberty.
+        game.insert_move(1, 0, &Player::O).unwrap();
+        assert_eq!(Some((0, 0)), game.check_win_at(1, 0));
+        assert_eq!(game.check_win(), game.check_win_at(1, 0));
+
+        // X fills its own last liberty. The X group's first cell comes before
+        // the stone just placed.
+        let mut game = GameBoard::from("..O..\n\
+                                        .OXO.\n\
+                                        .O.O.\n\
+                                        ..O..\n").unwrap();
+        game.insert_move(2, 2, &Player::X).unwrap();
+        assert_eq!(Some((1, 2)), game.check_win_at(2, 2));
+        assert_eq!(game.check_win(), game.check_win_at(2, 2));
+
+        // Groups the move didn't touch aren't checked.
+        let mut game = GameBoard::from("XO...\n\
+                                        O....\n\
+                                        .....\n\
+                                        .....\n").unwrap();
+        game.insert_move(3, 4, &Player::X).unwrap();
+        assert_eq!(None, game.check_win_at(3, 4));
+        assert_eq!(Some((0, 0)), game.check_win());
+        assert_eq!(None, game.check_win_at(2, 2));
+
+        game.insert_move(0, 2, &Player::O).unwrap();
+        game.set_flood_limit(1);
+        assert!(game.try_check_win_at(0, 2).is_err());
+
+        // Cells off the board aren't a win, or are an error when asked with
+        // try_check_win_at.
+        for &(h, w) in &[(4, 0), (0, 5), (usize::MAX, usize::MAX)] {
+            assert_eq!(None, game.check_win_at(h, w));
+            match game.try_check_win_at(h, w) {
+                Err(NogoError::Internal(message)) => assert!(message.ends_with("is off the board")),
+                r => panic!("{} {} gave {:?}", h, w, r),
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_with_dims() {
+        let contents = "....\n.O..\n....\n....\n....\n";
+
+        let game = GameBoard::from_with_dims(contents, 5, 4).unwrap();
+        assert_eq!(Some('O'), game.get(1, 1));
+
+        for &(height, width) in &[(4, 4), (5, 5), (4, 5)] {
+            match GameBoard::from_with_dims(contents, height, width) {
+                Err(NogoError::CorruptFile { .. }) => {},
+                r => panic!("{}x{} gave {:?}", height, width, r),
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_ragged() {
+        match GameBoard::from("....\n...\n....\n....\n") {
+            Err(NogoError::CorruptFile { line: 2, ref reason }) if reason == "row 1 has wrong width" => {},
+            r => panic!("short row gave {:?}", r),
+        }
+
+        // Blank lines still count towards the line.
+        match GameBoard::from("\n....\n.....\n....\n....\n") {
+            Err(NogoError::CorruptFile { line: 3, .. }) => {},
+            r => panic!("long row gave {:?}", r),
+        }
+
+        match GameBoard::from("") {
+            Err(NogoError::CorruptFile { line: 0, .. }) => {},
+            r => panic!("no rows gave {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_from_bad_char() {
+        match GameBoard::from("....\n.Q..\n....\n....\n") {
+            Err(NogoError::CorruptFile { line: 2, ref reason }) if reason == "row 1 has 'Q'" => {},
+            r => panic!("Q gave {:?}", r),
+        }
+
+        // Lowercase stones were once used to mark checked stones.
+        match GameBoard::from("....\n.o..\n....\n...7\n") {
+            Err(NogoError::CorruptFile { .. }) => {},
+            r => panic!("o and 7 gave {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_cells() {
+        let board = GameBoard::from("O....\n\
+                                     .....\n\
+                                     .....\n\
+                                     ...X.\n").unwrap();
+        let cells = board.cells().collect::<Vec<_>>();
+
+        assert_eq!(4 * 5, cells.len());
+        assert_eq!((0, 0, 'O'), cells[0]);
+        assert_eq!((0, 1, '.'), cells[1]);
+        assert_eq!((1, 0, '.'), cells[5]);
+        assert_eq!((3, 3, 'X'), cells[3 * 5 + 3]);
+        assert_eq!((3, 4, '.'), cells[cells.len() - 1]);
+    }
+
+    #[test]
+    fn test_clone_eq() {
+        let mut board = GameBoard::new(4, 5).unwrap();
+        board.insert_move(1, 2, &Player::O).unwrap();
+
+        let mut copy = board.clone();
+        assert_eq!(board, copy);
+
+        copy.insert_move(3, 3, &Player::X).unwrap();
+        assert_ne!(board, copy);
+        assert_eq!(Some('.'), board.get(3, 3));
+
+        // Only the stones count, not how they were placed.
+        copy.undo();
+        assert_eq!(board, copy);
+        assert_eq!(board, GameBoard::from(".....\n..O..\n.....\n.....\n").unwrap());
+        assert_ne!(GameBoard::new(4, 5).unwrap(), GameBoard::new(5, 4).unwrap());
+    }
+
+    #[test]
+    fn test_save() {
+        let mut game = GameBoard::new(4, 5).unwrap();
+        game.insert_move(0, 4, &Player::O).unwrap();
+        game.insert_move(3, 0, &Player::X).unwrap();
+
+        let mut saved = b"header\n".to_vec();
+        game.save(&mut saved).unwrap();
+
+        let saved = String::from_utf8(saved).unwrap();
+        assert_eq!("header\n....O\n.....\n.....\nX....\n", saved);
+        assert_eq!(game.render(), GameBoard::from(&saved["header".len()..]).unwrap().render());
+    }
+
+    #[test]
+    fn test_get() {
+        let game = GameBoard::from("X...\n\
+                                    ....\n\
+                                    ....\n\
+                                    ...O\n").unwrap();
+
+        assert_eq!(Some('X'), game.get(0, 0));
+        assert_eq!(Some('O'), game.get(3, 3));
+        assert_eq!(Some('.'), game.get(3, 2));
+        assert_eq!(None, game.get(4, 0));
+        assert_eq!(None, game.get(0, 4));
+        assert_eq!(None, game.get(usize::MAX, usize::MAX));
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut game = GameBoard::new(4, 4).unwrap();
+        assert_eq!(None, game.undo());
+
+        game.insert_move(1, 2, &Player::O).unwrap();
+        game.insert_move(3, 0, &Player::X).unwrap();
+
+        assert_eq!(Some((3, 0)), game.undo());
+        assert_eq!(Some('.'), game.get(3, 0));
+        assert_eq!(Some('O'), game.get(1, 2));
+        assert_eq!(&[(1, 2, 'O')], game.get_history());
+
+        assert_eq!(Some((1, 2)), game.undo());
+        assert_eq!(None, game.undo());
+        assert_eq!(GameBoard::new(4, 4).unwrap().render(), game.render());
+    }
+
+    #[test]
+    fn test_is_full() {
+        let mut game = GameBoard::from("OXOX\n\
+                                        XOXO\n\
+                                        OXOX\n\
+                                        XOX.\n").unwrap();
+        assert!(!game.is_full());
+
+        game.insert_move(3, 3, &Player::O).unwrap();
+        assert!(game.is_full());
+        assert!(!GameBoard::new(4, 4).unwrap().is_full());
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut game = GameBoard::from("OXOX\n\
+                                        XOXO\n\
+                                        OXOX\n\
+                                        XOX.\n").unwrap();
+        assert_eq!(7, game.stone_count(&Player::O));
+        assert_eq!(8, game.stone_count(&Player::X));
+        assert_eq!(1, game.empty_count());
+
+        game.insert_move(3, 3, &Player::O).unwrap();
+        assert_eq!(8, game.stone_count(&Player::O));
+        assert_eq!(0, game.empty_count());
+
+        let cramped = GameBoard::from(".O.O\n\
+                                       O.O.\n\
+                                       .O.O\n\
+                                       O.O.\n").unwrap();
+        assert_eq!(8, cramped.stone_count(&Player::O));
+        assert_eq!(0, cramped.stone_count(&Player::X));
+        assert_eq!(8, cramped.empty_count());
+        assert_eq!(30, GameBoard::new(5, 6).unwrap().empty_count());
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut game = GameBoard::new(6, 5).unwrap();
+        let vec = vec![     
+                     //   0    1    2    3    4
+            /* 0 */ vec!['X', '.', 'O', '.', 'O'],
+            /* 1 */ vec!['.', 'O', '.', 'X', '.'],
+            /* 2 */ vec!['.', '.', 'X', '.', '.'],
+            /* 3 */ vec!['.', 'X', '.', 'O', '.'],
+            /* 4 */ vec!['O', '.', 'X', '.', 'X'],
+            /* 5 */ vec!['O', '.', 'X', '.', 'X']
+        ];
+
+        game.insert_move(0, 0, &Player::X).unwrap();
+        game.insert_move(1, 3, &Player::X).unwrap();
+        game.insert_move(2, 2, &Player::X).unwrap();
+        game.insert_move(3, 1, &Player::X).unwrap();
+        game.insert_move(4, 2, &Player::X).unwrap();
+        game.insert_move(4, 4, &Player::X).unwrap();
+        game.insert_move(5, 2, &Player::X).unwrap();
+        game.insert_move(5, 4, &Player::X).unwrap();
+
+        game.insert_move(0, 2, &Player::O).unwrap();
+        game.insert_move(0, 4, &Player::O).unwrap();
+        game.insert_move(1, 1, &Player::O).unwrap();
+        game.insert_move(3, 3, &Player::O).unwrap();
+        game.insert_move(4, 0, &Player::O).unwrap();
+        game.insert_move(5, 0, &Player::O).unwrap();
+
+        assert_eq!(game.board, vec);
+    }
+
+    #[test]
+    fn test_dimension_bounds() {
+        let rows = |height: usize, width: usize| format!("{}\n", ".".repeat(width)).repeat(height);
+
+        for &(n, ok) in &[(3, false), (4, true), (1000, true), (1001, false)] {
+            for &(height, width) in &[(n, 4), (4, n)] {
+                let new  = GameBoard::new(height, width);
+                let from = GameBoard::from(&rows(height, width));
+
+                if ok {
+                    let (new, from) = (new.unwrap(), from.unwrap());
+                    assert_eq!((height, width), (new.get_height(), new.get_width()));
+                    assert_eq!((height, width), (from.get_height(), from.get_width()));
+                    continue;
+                }
+
+                match new {
+                    Err(NogoError::InvalidDimension) => {},
+                    r => panic!("{}x{} gave {:?}", height, width, r.map(|_| ())),
+                }
+                match from {
+                    Err(NogoError::CorruptFile { line: 0, ref reason }) =>
+                        assert_eq!(format!("board is {}x{} but sides must be 4 to 1000", height, width), *reason),
+                    r => panic!("{}x{} gave {:?}", height, width, r.map(|_| ())),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_empty() {
+        for contents in &["", "\n\n", "  \n\t\n"] {
+            match GameBoard::from(contents) {
+                Err(NogoError::CorruptFile { line: 0, ref reason }) => assert_eq!("board has no rows", reason),
+                r => panic!("{:?} gave {:?}", contents, r.map(|_| ())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_from_dims() {
+        let game = GameBoard::empty_from_dims("6 5").unwrap();
+        assert_eq!((6, 5), (game.get_height(), game.get_width()));
+
+        let game = GameBoard::empty_from_dims("9x12").unwrap();
+        assert_eq!((9, 12), (game.get_height(), game.get_width()));
+        assert_eq!(Some('.'), game.get(8, 11));
+
+        for dims in &["9", "9 x", "a b", "9 9 9", "3x9"] {
+            match GameBoard::empty_from_dims(dims) {
+                Err(NogoError::InvalidDimension) => {},
+                r => panic!("{:?} gave {:?}", dims, r),
+            }
+        }
+    }
+
+    #[test]
+    fn test_move_error() {
+        let mut game = GameBoard::new(6, 5).unwrap();
+        game.insert_move(2, 3, &Player::X).unwrap();
+
+        assert_eq!(None, game.move_error(0, 0, &Player::O).unwrap());
+        assert_eq!(None, game.move_error(5, 4, &Player::X).unwrap());
+        assert_eq!(Some(MoveError::RowOutOfBounds),    game.move_error(6, 0, &Player::O).unwrap());
+        assert_eq!(Some(MoveError::ColumnOutOfBounds), game.move_error(0, 5, &Player::O).unwrap());
+        assert_eq!(Some(MoveError::Occupied),          game.move_error(2, 3, &Player::O).unwrap());
+        assert_eq!(Some(MoveError::Occupied),          game.move_error(2, 3, &Player::X).unwrap());
+
+        // Nothing was placed while checking.
+        assert_eq!(Some('.'), game.get(0, 0));
+    }
+
+    #[test]
+    fn test_can_place() {
+        let mut board = GameBoard::new(4, 5).unwrap();
+        board.insert_move(1, 2, &Player::X).unwrap();
+
+        assert_eq!(Ok(()), board.can_place(0, 0));
+        assert_eq!(Ok(()), board.can_place(3, 4));
+        assert_eq!(Err(MoveError::RowOutOfBounds), board.can_place(4, 0));
+        assert_eq!(Err(MoveError::ColumnOutOfBounds), board.can_place(0, 5));
+        assert_eq!(Err(MoveError::Occupied), board.can_place(1, 2));
+
+        // Nothing was placed.
+        assert_eq!(1, board.get_history().len());
+    }
+
+    #[test]
+    fn test_insert_move_error() {
+        let mut game = GameBoard::new(6, 5).unwrap();
+        assert_eq!(Ok(()), game.insert_move(2, 3, &Player::X));
+
+        assert_eq!(Err(MoveError::RowOutOfBounds),    game.insert_move(6, 0, &Player::O));
+        assert_eq!(Err(MoveError::ColumnOutOfBounds), game.insert_move(0, 5, &Player::O));
+        assert_eq!(Err(MoveError::Occupied),          game.insert_move(2, 3, &Player::O));
+        assert_eq!("Position already taken", game.insert_move(2, 3, &Player::X).unwrap_err().to_string());
+
+        game.set_forbid_suicide(true);
+        game.insert_move(0, 1, &Player::X).unwrap();
+        game.insert_move(1, 0, &Player::X).unwrap();
+        assert_eq!(Err(MoveError::Suicide), game.insert_move(0, 0, &Player::O));
+        assert_eq!(3, game.get_history().len());
+
+        // X's two stones are more than the flood fill limit allows.
+        game.set_flood_limit(1);
+        game.insert_move(0, 2, &Player::X).unwrap();
+        assert_eq!(Err(MoveError::GroupTooBig), game.insert_move(0, 0, &Player::O));
+    }
+
+    #[test]
+    fn test_flood_limit() {
+        let mut game = GameBoard::from("XXXXXX\n\
+                                        XXXXXX\n\
+                                        XXXXXX\n\
+                                        XXXXXX\n").unwrap();
+
+        assert_eq!(Some((0, 0)), game.try_check_win().unwrap());
+
+        game.set_flood_limit(10);
+        match game.try_check_win() {
+            Err(NogoError::Internal(_)) => {},
+            r => panic!("expected internal error, got {:?}", r),
+        }
+
+        // Too big to walk, so whether a move joining it is suicide can't be
+        // told either.
+        game.board[3][5] = '.';
+        assert!(game.would_self_capture(3, 5, &Player::X).is_err());
+        assert!(game.mobility(&Player::X).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let game = GameBoard::from("X...\n\
+                                    .O..\n\
+                                    ....\n\
+                                    ...X\n").unwrap();
+
+        assert_eq!("/----\\\n\
+                    |X...|\n\
+                    |.O..|\n\
+                    |....|\n\
+                    |...X|\n\
+                    \\----/\n", game.render());
+    }
+
+    #[test]
+    fn test_print_to() {
+        let game = GameBoard::from("X...\n\
+                                    .O..\n\
+                                    ....\n\
+                                    ...X\n").unwrap();
+        let mut out = Vec::new();
+
+        game.print_to(&mut out).unwrap();
+        assert_eq!(b"/----\\\n|X...|\n|.O..|\n|....|\n|...X|\n\\----/\n".to_vec(), out);
+    }
+
+    #[test]
+    fn test_render_labeled() {
+        let mut game = GameBoard::new(4, 5).unwrap();
+        game.insert_move(0, 4, &Player::O).unwrap();
+
+        // Leading spaces would be lost after a \ line break.
+        assert_eq!(concat!("  01234\n",
+                           " /-----\\\n",
+                           "0|....O|\n",
+                           "1|.....|\n",
+                           "2|.....|\n",
+                           "3|.....|\n",
+                           " \\-----/\n"), game.render_labeled('.'));
+
+        let mut game = GameBoard::new(11, 11).unwrap();
+        game.insert_move(10, 10, &Player::X).unwrap();
+        let labeled = game.render_labeled('+');
+        let lines: Vec<&str> = labeled.lines().collect();
+
+        // Tens then units, each above its column.
+        assert_eq!("             1", lines[0]);
+        assert_eq!("   01234567890", lines[1]);
+        assert_eq!("  /-----------\\", lines[2]);
+        assert_eq!(" 0|+++++++++++|", lines[3]);
+        assert_eq!("10|++++++++++X|", lines[13]);
+        // Column 10's stone sits under its 1 and 0.
+        assert_eq!(Some(lines[0].len() - 1), lines[13].find('X'));
+        assert_eq!(Some(lines[1].len() - 1), lines[13].find('X'));
+    }
+
+    #[test]
+    fn test_render_highlight() {
+        let mut game = GameBoard::new(4, 4).unwrap();
+        assert_eq!(game.render(), game.render_highlight('.'));
+
+        game.insert_move(0, 0, &Player::O).unwrap();
+        game.insert_move(3, 3, &Player::X).unwrap();
+        game.insert_move(1, 1, &Player::O).unwrap();
+
+        // Only the latest O is marked.
+        assert_eq!("/----\\\n\
+                    |O...|\n\
+                    |.o..|\n\
+                    |....|\n\
+                    |...X|\n\
+                    \\----/\n", game.render_highlight('.'));
+
+        // Moves on with the next stone and back again on undo.
+        game.insert_move(2, 2, &Player::X).unwrap();
+        assert_eq!(Some((2, 2)), game.last_move());
+        assert!(game.render_highlight('.').contains("|..x.|"));
+        assert!(game.render_highlight('.').contains("|.O..|"));
+
+        game.undo();
+        assert!(game.render_highlight('.').contains("|.o..|"));
+        assert_eq!(Some('O'), game.get(1, 1));
+    }
+
+    #[test]
+    fn test_puts_in_atari() {
+        let game = GameBoard::from("......\n\
+                                    ....OX\n\
+                                    ......\n\
+                                    .XX...\n").unwrap();
+
+        // X at (1, 5) has (0, 5) and (2, 5) left.
+        assert!(game.puts_in_atari(0, 5, &Player::O));
+        assert!(game.puts_in_atari(2, 5, &Player::O));
+        // The pair on row 3 keeps three liberties.
+        assert!(!game.puts_in_atari(2, 1, &Player::O));
+        // Not an opponent group for X.
+        assert!(!game.puts_in_atari(0, 5, &Player::X));
+    }
+
+    #[test]
+    fn test_render_with_empty() {
+        let mut game = GameBoard::new(4, 5).unwrap();
+        game.insert_move(0, 0, &Player::O).unwrap();
+        game.insert_move(3, 4, &Player::X).unwrap();
+
+        assert_eq!("/-----\\\n\
+                    |O++++|\n\
+                    |+++++|\n\
+                    |+++++|\n\
+                    |++++X|\n\
+                    \\-----/\n", game.render_with_empty('+'));
+
+        // The board itself is unchanged.
+        assert_eq!(Some('.'), game.get(0, 1));
+    }
+
+    #[test]
+    fn test_all_liberties() {
+        let mut game = GameBoard::new(6, 5).unwrap();
+        game.board = vec![
+                     //   0    1    2    3    4
+            /* 0 */ vec!['X', '.', '.', '.', '.'],
+            /* 1 */ vec!['.', 'O', '.', '.', '.'],
+            /* 2 */ vec!['.', '.', '.', '.', '.'],
+            /* 3 */ vec!['.', '.', '.', '.', '.'],
+            /* 4 */ vec!['.', '.', '.', 'X', 'X'],
+            /* 5 */ vec!['.', '.', '.', 'X', '.']
+        ];
+
+        let f = false;
+        let t = true;
+        let expected = vec![
+            vec![f, t, f, f, f],
+            vec![t, f, t, f, f],
+            vec![f, t, f, f, f],
+            vec![f, f, f, t, t],
+            vec![f, f, t, f, f],
+            vec![f, f, t, f, t]
+        ];
+
+        assert_eq!(expected, game.all_liberties());
+    }
+
+    #[test]
+    fn test_centroid() {
+        let mut game = GameBoard::new(6, 5).unwrap();
+        game.board = vec![
+                     //   0    1    2    3    4
+            /* 0 */ vec!['O', 'O', '.', '.', '.'],
+            /* 1 */ vec!['O', 'O', '.', '.', '.'],
+            /* 2 */ vec!['.', '.', '.', '.', '.'],
+            /* 3 */ vec!['.', '.', '.', '.', '.'],
+            /* 4 */ vec!['.', '.', '.', '.', '.'],
+            /* 5 */ vec!['.', '.', '.', '.', '.']
+        ];
+
+        assert_eq!(Some((0.5, 0.5)), game.centroid(&Player::O));
+        assert_eq!(None, game.centroid(&Player::X));
+    }
+
+    #[test]
+    fn test_is_lost() {
+        let game = GameBoard::from("XOX..\n\
+                                    .X...\n\
+                                    .....\n\
+                                    .....\n").unwrap();
+
+        assert!(!game.is_lost(&Player::X));
+        assert!(game.is_lost(&Player::O));
+
+        let game = GameBoard::from("XO...\n\
+                                    .....\n\
+                                    .....\n\
+                                    .....\n").unwrap();
+
+        assert!(!game.is_lost(&Player::O));
+    }
+
+    #[test]
+    fn test_puzzle_string() {
+        let mut game = GameBoard::new(4, 5).unwrap();
+        game.insert_move(0, 1, &Player::O).unwrap();
+        game.insert_move(3, 2, &Player::X).unwrap();
+
+        let puzzle = game.to_puzzle_string(&Player::O);
+        assert_eq!("4x5-O-.O...............X..", puzzle);
+
+        let (loaded, next) = GameBoard::from_puzzle_string(&puzzle).unwrap();
+        assert_eq!(game.board, loaded.board);
+        assert_eq!(Player::O, next);
+
+        assert!(GameBoard::from_puzzle_string("4x5-O-.O..").is_err());
+        assert!(GameBoard::from_puzzle_string("4x5-Z-.O...............X..").is_err());
+        assert!(GameBoard::from_puzzle_string("4x5-O-.Q...............X..").is_err());
+    }
+
+    #[test]
+    fn test_would_self_capture() {
+        let game = GameBoard::from(".XO..\n\
+                                    XO...\n\
+                                    O....\n\
+                                    .....\n").unwrap();
+
+        // Corner surrounded by O with the X stones having no other liberty.
+        assert!(game.would_self_capture(0, 0, &Player::X).unwrap());
+        // O takes the X group's last liberty so it isn't self capture.
+        assert!(!game.would_self_capture(0, 0, &Player::O).unwrap());
+        assert!(!game.would_self_capture(0, 3, &Player::X).unwrap());
+    }
+
+    #[test]
+    fn test_is_suicide() {
+        let game = GameBoard::from(".X..\n\
+                                    OX..\n\
+                                    X...\n\
+                                    ...X\n").unwrap();
+
+        // O in the corner joins (1, 0) and the pair has no liberties left.
+        assert!(game.is_suicide(0, 0, &Player::O).unwrap());
+        // X in the corner still has (0, 2) through its neighbour.
+        assert!(!game.is_suicide(0, 0, &Player::X).unwrap());
+        // Bottom right corner with a liberty free.
+        assert!(!game.is_suicide(3, 2, &Player::O).unwrap());
+        // Taken and off the board.
+        assert!(!game.is_suicide(1, 0, &Player::O).unwrap());
+        assert!(!game.is_suicide(4, 0, &Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_forbid_suicide() {
+        let mut game = GameBoard::from(".X..\n\
+                                        OX..\n\
+                                        X...\n\
+                                        ....\n").unwrap();
+
+        assert_eq!(None, game.move_error(0, 0, &Player::O).unwrap());
+
+        game.set_forbid_suicide(true);
+        assert_eq!(Some(MoveError::Suicide), game.move_error(0, 0, &Player::O).unwrap());
+        assert!(game.insert_move(0, 0, &Player::O).is_err());
+        assert_eq!(Some('.'), game.get(0, 0));
+        assert!(game.insert_move(0, 0, &Player::X).is_ok());
+    }
+
+    #[test]
+    fn test_mobility() {
+        let open = GameBoard::new(4, 4).unwrap();
+        assert_eq!(16, open.mobility(&Player::X).unwrap());
+
+        let cramped = GameBoard::from(".O.O\n\
+                                       O.O.\n\
+                                       .O.O\n\
+                                       O.O.\n").unwrap();
+
+        // Every empty cell is surrounded by O stones.
+        assert_eq!(0, cramped.mobility(&Player::X).unwrap());
+        assert_eq!(8, cramped.mobility(&Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_mobility_balance() {
+        let open = GameBoard::new(4, 4).unwrap();
+        assert_eq!(0, open.mobility_balance().unwrap());
+
+        // X can't play anywhere without capturing itself.
+        let cramped = GameBoard::from(".O.O\n\
+                                       O.O.\n\
+                                       .O.O\n\
+                                       O.O.\n").unwrap();
+        assert!(cramped.mobility_balance().unwrap() > 0);
+
+        let cramped = GameBoard::from(".X.X\n\
+                                       X.X.\n\
+                                       .X.X\n\
+                                       X.X.\n").unwrap();
+        assert!(cramped.mobility_balance().unwrap() < 0);
+    }
+
+    #[test]
+    fn test_early_capture() {
+        let game = GameBoard::from("....\n\
+                                    .OX.\n\
+                                    ....\n\
+                                    ....\n").unwrap();
+        assert_eq!(None, game.early_capture(&Player::O).unwrap());
+
+        // X's last move could have filled its own last liberty.
+        let game = GameBoard::from("XO..\n\
+                                    O...\n\
+                                    ....\n\
+                                    ..X.\n").unwrap();
+        assert_eq!(None, game.early_capture(&Player::X).unwrap());
+        assert_eq!(None, game.early_capture(&Player::O).unwrap());
+
+        // Two captures on opposite corners can't both be the last move.
+        let game = GameBoard::from("XO..\n\
+                                    O...\n\
+                                    ...O\n\
+                                    ..OX\n").unwrap();
+        assert_eq!(Some((0, 0)), game.early_capture(&Player::O).unwrap());
+        assert_eq!(Some((0, 0)), game.early_capture(&Player::X).unwrap());
+    }
+
+    #[test]
+    fn test_capture_race() {
+        let game = GameBoard::from("OOX..\n\
+                                    OXX..\n\
+                                    OX...\n\
+                                    .X...\n").unwrap();
+
+        // O has (3, 0). X has (0, 3), (1, 3), (2, 2), (3, 2), and (3, 0).
+        assert_eq!(-4, game.capture_race((0, 0), (1, 1)));
+        assert_eq!(4, game.capture_race((1, 1), (0, 0)));
+        assert_eq!(0, game.capture_race((0, 0), (3, 4)));
+
+        // Off the board is treated like an empty cell.
+        assert_eq!(0, game.capture_race((0, 0), (4, 0)));
+        assert_eq!(0, game.capture_race((0, 5), (1, 1)));
+        assert_eq!(0, game.capture_race((usize::MAX, 0), (1, 1)));
+    }
+
+    #[test]
+    fn test_hash() {
+        let hash = |board: &GameBoard| {
+            let mut hasher = DefaultHasher::new();
+            board.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let mut played = GameBoard::new(4, 4).unwrap();
+        played.insert_move(1, 2, &Player::O).unwrap();
+        let loaded = GameBoard::from("....\n..O.\n....\n....\n").unwrap();
+        let other  = GameBoard::from("....\n..X.\n....\n....\n").unwrap();
+
+        assert_eq!(hash(&played), hash(&loaded));
+        assert_eq!(played.position_hash(), loaded.position_hash());
+        assert_ne!(hash(&played), hash(&other));
+        assert_ne!(played.position_hash(), other.position_hash());
+
+        let seen: HashSet<GameBoard> = vec![played, loaded, other].into_iter().collect();
+        assert_eq!(2, seen.len());
+    }
+
+    #[test]
+    fn test_decided_cache() {
+        let mut game = GameBoard::from("XO..\n\
+                                        ....\n\
+                                        ....\n\
+                                        ....\n").unwrap();
+        let mut cache = DecidedCache::new();
+        let open      = game.position_hash();
+
+        assert!(!game.is_decided_cached(&mut cache).unwrap());
+        assert_eq!(game.try_check_win().unwrap().is_some(), game.is_decided_cached(&mut cache).unwrap());
+
+        game.insert_move(1, 0, &Player::O).unwrap();
+        assert_ne!(open, game.position_hash());
+        assert!(game.is_decided_cached(&mut cache).unwrap());
+        assert_eq!(game.try_check_win().unwrap().is_some(), game.is_decided_cached(&mut cache).unwrap());
+
+        // The same position reached without history hashes the same.
+        let other = GameBoard::from("XO..\n\
+                                     O...\n\
+                                     ....\n\
+                                     ....\n").unwrap();
+        assert_eq!(other.position_hash(), game.position_hash());
+
+        // A cache can be shared by every board in a search.
+        assert!(other.is_decided_cached(&mut cache).unwrap());
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert!(game.is_decided_cached(&mut cache).unwrap());
+    }
+
+    #[test]
+    fn test_keeps_opponent_safe_moves() {
+        let game = GameBoard::from("....\n\
+                                    .X..\n\
+                                    X...\n\
+                                    OX..\n").unwrap();
+        let moves = game.keeps_opponent_safe_moves(&Player::O);
+
+        // Quiet: touches nothing, or leaves the X at (1, 1) two liberties.
+        assert!(moves.contains(&(0, 3)));
+        assert!(moves.contains(&(0, 1)));
+
+        // Threatening: (1, 0) and (2, 1) put the X at (2, 0) in atari, and
+        // (2, 1) and (3, 2) the X at (3, 1).
+        assert!(!moves.contains(&(1, 0)));
+        assert!(!moves.contains(&(2, 1)));
+        assert!(!moves.contains(&(3, 2)));
+        assert_eq!(12 - 3, moves.len());
+
+        // O's only stone touches no empty cell, so nothing X plays affects it.
+        assert_eq!(12, game.keeps_opponent_safe_moves(&Player::X).len());
+    }
+
+    #[test]
+    fn test_cell_position() {
+        let game = GameBoard::new(4, 5).unwrap();
+
+        for &(h, w) in &[(0, 0), (0, 4), (3, 0), (3, 4)] {
+            assert_eq!(CellKind::Corner, game.cell_position(h, w));
+            assert_eq!(2, game.neighbors(h, w).count());
+        }
+
+        for &(h, w) in &[(0, 2), (2, 0), (3, 1), (1, 4)] {
+            assert_eq!(CellKind::Edge, game.cell_position(h, w));
+            assert_eq!(3, game.neighbors(h, w).count());
+        }
+
+        assert_eq!(CellKind::Interior, game.cell_position(1, 1));
+        assert_eq!(CellKind::Interior, game.cell_position(2, 3));
+        assert_eq!(4, game.neighbors(2, 3).count());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let game = GameBoard::new(4, 5).unwrap();
+
+        assert_eq!(None,         Direction::Left.step(2, 0, 4, 5));
+        assert_eq!(None,         Direction::Up.step(0, 2, 4, 5));
+        assert_eq!(None,         Direction::Right.step(2, 4, 4, 5));
+        assert_eq!(None,         Direction::Down.step(3, 2, 4, 5));
+        assert_eq!(Some((2, 1)), Direction::Left.step(2, 2, 4, 5));
+        assert_eq!(Some((1, 2)), Direction::Up.step(2, 2, 4, 5));
+        assert_eq!(Some((2, 3)), Direction::Right.step(2, 2, 4, 5));
+        assert_eq!(Some((3, 2)), Direction::Down.step(2, 2, 4, 5));
+
+        // Left, up, right, then down, leaving out steps off the board.
+        assert_eq!(vec![(0, 1), (1, 0)], game.neighbors(0, 0).collect::<Vec<_>>());
+        assert_eq!(vec![(3, 3), (2, 4)], game.neighbors(3, 4).collect::<Vec<_>>());
+        assert_eq!(vec![(0, 1), (0, 3), (1, 2)], game.neighbors(0, 2).collect::<Vec<_>>());
+        assert_eq!(vec![(2, 2), (1, 3), (2, 4), (3, 3)], game.neighbors(2, 3).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_print_frame() {
+        // Same steps print used to take one character at a time.
+        fn per_char(game: &GameBoard) -> String {
+            let mut out = String::from("/");
+            for _ in 0..game.width {
+                out.push('-');
+            }
+            out.push_str("\\\n");
+
+            for line in &game.board {
+                out.push('|');
+                for ch in line {
+                    out.push(*ch);
+                }
+                out.push_str("|\n");
+            }
+
+            out.push('\\');
+            for _ in 0..game.width {
+                out.push('-');
+            }
+            out.push_str("/\n");
+            out
+        }
+
+        let game = GameBoard::from("XO...\n\
+                                    .....\n\
+                                    ..O..\n\
+                                    ....X\n").unwrap();
+        assert_eq!(per_char(&game).as_bytes(), game.render().as_bytes());
+
+        let game = GameBoard::new(1000, 1000).unwrap();
+        assert_eq!(per_char(&game), game.render());
+    }
+
+    #[test]
+    fn test_is_losing_move() {
+        let game = GameBoard::from("XO...\n\
+                                    .O...\n\
+                                    ...OX\n\
+                                    ..OX.\n").unwrap();
+
+        // Captures the X in the corner.
+        assert!(game.is_losing_move(1, 0, &Player::O).unwrap());
+        // Takes the last liberty of the X at (3, 3).
+        assert!(game.is_losing_move(3, 4, &Player::O).unwrap());
+        assert!(!game.is_losing_move(3, 4, &Player::X).unwrap());
+        assert!(!game.is_losing_move(0, 2, &Player::O).unwrap());
+        assert!(!game.is_losing_move(1, 0, &Player::X).unwrap());
+    }
+
+    #[test]
+    fn test_is_reachable() {
+        assert!(GameBoard::new(4, 4).unwrap().is_reachable().unwrap());
+
+        let plausible = GameBoard::from("O...\n\
+                                         .X..\n\
+                                         ..O.\n\
+                                         ....\n").unwrap();
+        assert!(plausible.is_reachable().unwrap());
+
+        // X can never have more stones than O.
+        let parity = GameBoard::from("O...\n\
+                                      .X..\n\
+                                      ..X.\n\
+                                      ....\n").unwrap();
+        assert!(!parity.is_reachable().unwrap());
+
+        // O's last move at (1, 0) captured the X in the corner.
+        let captured = GameBoard::from("XO..\n\
+                                        O...\n\
+                                        ...X\n\
+                                        ....\n").unwrap();
+        assert!(captured.is_reachable().unwrap());
+
+        // X moved last, but one move can't have filled the last liberties of
+        // both X corners.
+        let early = GameBoard::from("XO..\n\
+                                     O.X.\n\
+                                     .X.O\n\
+                                     ..OX\n").unwrap();
+        assert!(!early.is_reachable().unwrap());
+    }
+
+    #[test]
+    fn test_phase() {
+        let mut game = GameBoard::new(10, 10).unwrap();
+        assert_eq!(Phase::Opening, game.phase());
+
+        // 19 filled.
+        for w in 0..10 {
+            game.board[0][w] = 'O';
+        }
+        for w in 0..9 {
+            game.board[1][w] = 'X';
+        }
+        assert_eq!(Phase::Opening, game.phase());
+
+        game.board[1][9] = 'X';
+        assert_eq!(Phase::Middlegame, game.phase());
+
+        for h in 2..7 {
+            for w in 0..10 {
+                game.board[h][w] = 'O';
+            }
+        }
+        assert_eq!(Phase::Middlegame, game.phase());
+
+        game.board[7][0] = 'X';
+        assert_eq!(Phase::Endgame, game.phase());
+    }
+
+    #[test]
+    fn test_from_sgf() {
+        let game = GameBoard::from_sgf("(;FF[4]GM[1]SZ[5:4]\n AB[aa][ec]AW[bd];B[cb];W[]\n;W[db])").unwrap();
+        assert_eq!(4, game.get_height());
+        assert_eq!(5, game.get_width());
+        assert_eq!("O....\n..OX.\n....O\n.X...\n",
+                   (0..4).map(|h| (0..5).filter_map(|w| game.get(h, w)).collect::<String>() + "\n")
+                         .collect::<String>());
+        assert_eq!(&[(1, 2, 'O'), (1, 3, 'X')], game.get_history());
+
+        // Round trips through to_sgf.
+        let mut played = GameBoard::new(7, 7).unwrap();
+        played.insert_move(3, 3, &Player::O).unwrap();
+        played.insert_move(2, 4, &Player::X).unwrap();
+        let loaded = GameBoard::from_sgf(&played.to_sgf(None).unwrap()).unwrap();
+        assert_eq!(played, loaded);
+        assert_eq!(played.get_history(), loaded.get_history());
+
+        assert_eq!(19, GameBoard::from_sgf("(;AB[ss])").unwrap().get_width());
+
+        for bad in &["", ";SZ[4]", "(;SZ[4]AB[ee])", "(;SZ[4]AB[aa]AW[aa])", "(;SZ[4]B[aa];W[aa])",
+                     "(;SZ[four])", "(;SZ[2])", "(;SZ[4]AB[aa)", "(;SZ[4]AB)", "(;SZ[4]AB[aa]%)"] {
+            match GameBoard::from_sgf(bad) {
+                Err(NogoError::CorruptFile { .. }) | Err(NogoError::InvalidDimension) => {},
+                r => panic!("{:?} gave {:?}", bad, r),
+            }
+        }
+    }
+
+    #[test]
+    fn test_sgf_result() {
+        use nogo::EndReason;
+
+        let mut game = GameBoard::new(7, 7).unwrap();
+        game.insert_move(3, 3, &Player::O).unwrap();
+        game.insert_move(2, 4, &Player::X).unwrap();
+
+        let result = GameResult { winner: Some(Player::X), reason: EndReason::Capture };
+
+        assert_eq!("(;FF[4]GM[1]SZ[7]RE[W+];B[dd];W[ec])", game.to_sgf(Some(&result)).unwrap());
+        assert_eq!("(;FF[4]GM[1]SZ[7];B[dd];W[ec])", game.to_sgf(None).unwrap());
+
+        // The last column and row SGF can name are "Z".
+        let mut game = GameBoard::new(52, 52).unwrap();
+        game.insert_move(51, 51, &Player::O).unwrap();
+        assert_eq!("(;FF[4]GM[1]SZ[52];B[ZZ])", game.to_sgf(None).unwrap());
+
+        for &(height, width) in &[(53, 52), (52, 53)] {
+            match GameBoard::new(height, width).unwrap().to_sgf(None) {
+                Err(NogoError::InvalidDimension) => {},
+                r => panic!("{}x{} gave {:?}", height, width, r),
+            }
+        }
     }
 }
@@ -0,0 +1,253 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Just enough JSON for save files. Numbers can only be whole and positive.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Number(usize),
+    Str(String),
+    Array(Vec<Value>),
+    // Keys are kept in the order they were written.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Value of key if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match *self {
+            Value::Object(ref fields) => fields.iter().find(|f| f.0 == key).map(|f| &f.1),
+            _                         => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<usize> {
+        match *self {
+            Value::Number(n) => Some(n),
+            _                => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Value::Str(ref s) => Some(s),
+            _                 => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match *self {
+            Value::Array(ref items) => Some(items),
+            _                       => None,
+        }
+    }
+
+    /// Writes the value as JSON, nested objects indented past indent.
+    fn write(&self, f: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        match *self {
+            Value::Null          => write!(f, "null"),
+            Value::Number(n)     => write!(f, "{}", n),
+            Value::Str(ref s)    => write_str(f, s),
+            Value::Array(ref items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.write(f, indent)?;
+                }
+                write!(f, "]")
+            },
+            Value::Object(ref fields) => {
+                writeln!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    write!(f, "{:1$}", "", indent + 4)?;
+                    write_str(f, key)?;
+                    write!(f, ": ")?;
+                    value.write(f, indent + 4)?;
+                    if i + 1 < fields.len() {
+                        write!(f, ",")?;
+                    }
+                    writeln!(f)?;
+                }
+                write!(f, "{:1$}}}", "", indent)
+            },
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    /// Writes the value as JSON, one object field per line.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Writes s as a quoted JSON string.
+fn write_str(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for ch in s.chars() {
+        match ch {
+            '"'               => write!(f, "\\\"")?,
+            '\\'              => write!(f, "\\\\")?,
+            '\n'              => write!(f, "\\n")?,
+            c if c < ' '      => write!(f, "\\u{:04x}", c as u32)?,
+            c                 => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Parses a whole JSON document. None if it isn't valid or uses something
+/// Value can't hold.
+pub fn parse(text: &str) -> Option<Value> {
+    let mut chars = text.chars().peekable();
+    let value = parse_value(&mut chars)?;
+
+    skip_space(&mut chars);
+    if chars.next().is_some() {
+        return None;
+    }
+
+    Some(value)
+}
+
+fn skip_space(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Option<Value> {
+    skip_space(chars);
+
+    match *chars.peek()? {
+        'n' => {
+            for expected in "null".chars() {
+                if chars.next()? != expected {
+                    return None;
+                }
+            }
+            Some(Value::Null)
+        },
+        '0'..='9' => {
+            let mut n: usize = 0;
+            while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+                n = n.checked_mul(10)?.checked_add(d as usize)?;
+                chars.next();
+            }
+            Some(Value::Number(n))
+        },
+        '"' => parse_str(chars).map(Value::Str),
+        '[' => {
+            chars.next();
+            let mut items = Vec::new();
+
+            skip_space(chars);
+            if chars.peek() == Some(&']') {
+                chars.next();
+                return Some(Value::Array(items));
+            }
+
+            loop {
+                items.push(parse_value(chars)?);
+                skip_space(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    ']' => return Some(Value::Array(items)),
+                    _   => return None,
+                }
+            }
+        },
+        '{' => {
+            chars.next();
+            let mut fields = Vec::new();
+
+            skip_space(chars);
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                return Some(Value::Object(fields));
+            }
+
+            loop {
+                skip_space(chars);
+                let key = parse_str(chars)?;
+                skip_space(chars);
+                if chars.next()? != ':' {
+                    return None;
+                }
+                fields.push((key, parse_value(chars)?));
+
+                skip_space(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    '}' => return Some(Value::Object(fields)),
+                    _   => return None,
+                }
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Parses a quoted string, starting at the opening quote.
+fn parse_str(chars: &mut Peekable<Chars>) -> Option<String> {
+    if chars.next()? != '"' {
+        return None;
+    }
+
+    let mut s = String::new();
+    loop {
+        match chars.next()? {
+            '"'  => return Some(s),
+            '\\' => match chars.next()? {
+                '"'  => s.push('"'),
+                '\\' => s.push('\\'),
+                '/'  => s.push('/'),
+                'n'  => s.push('\n'),
+                't'  => s.push('\t'),
+                'r'  => s.push('\r'),
+                'u'  => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    let code = u32::from_str_radix(&hex, 16).ok()?;
+                    s.push(::std::char::from_u32(code)?);
+                },
+                _    => return None,
+            },
+            c    => s.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let value = Value::Object(vec![
+            (String::from("n"),     Value::Number(42)),
+            (String::from("s"),     Value::Str(String::from("a \"b\"\\\n"))),
+            (String::from("none"),  Value::Null),
+            (String::from("list"),  Value::Array(vec![Value::Number(1), Value::Array(vec![])])),
+            (String::from("inner"), Value::Object(vec![(String::from("x"), Value::Number(0))])),
+        ]);
+
+        let text = value.to_string();
+        assert_eq!(Some(value), parse(&text));
+    }
+
+    #[test]
+    fn test_parse() {
+        let value = parse(" { \"a\" : [ \"O.\\u0058\" ] , \"b\":7 } ").unwrap();
+        assert_eq!(Some("O.X"), value.get("a").and_then(|a| a.as_array()).and_then(|a| a[0].as_str()));
+        assert_eq!(Some(7), value.get("b").and_then(|b| b.as_number()));
+        assert_eq!(None, value.get("c"));
+
+        assert_eq!(None, parse("{\"a\": 1,}"));
+        assert_eq!(None, parse("[1, 2] 3"));
+        assert_eq!(None, parse("-1"));
+        assert_eq!(None, parse("\"open"));
+    }
+}
@@ -0,0 +1,25 @@
+//! Nogo game engine. The nogors binary is a thin wrapper around Nogo, the
+//! same pieces can be used to drive a game from anywhere else.
+
+pub mod nogo;
+pub mod computer;
+pub mod game_board;
+mod json;
+
+pub use nogo::BoardSource;
+pub use nogo::Config;
+pub use nogo::GameOutcome;
+pub use nogo::Nogo;
+pub use nogo::NogoBuilder;
+pub use nogo::NogoError;
+pub use nogo::Player;
+pub use nogo::Scoreboard;
+pub use computer::Computer;
+pub use computer::DeterministicStrategy;
+pub use computer::Difficulty;
+pub use computer::RandomStrategy;
+pub use computer::SequenceStart;
+pub use computer::Strategy;
+pub use game_board::DecidedCache;
+pub use game_board::GameBoard;
+pub use game_board::MoveError;
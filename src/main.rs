@@ -1,15 +1,13 @@
+extern crate nogors;
+
 use std::env;
 use std::process;
 
-mod nogo;
-mod computer;
-mod game_board;
-
-use nogo::Nogo;
-use nogo::NogoError;
+use nogors::Nogo;
+use nogors::NogoError;
 
 fn main() {
-    let mut nogo = match Nogo::new(env::args()) {
+    let mut nogo = match Nogo::from_args(env::args()) {
         Err(e) => {
             match_error(&e);
             // Process should have already been exited. Keeps compiler from
@@ -26,8 +24,8 @@ fn main() {
 
 fn match_error(err: &NogoError) {
     match *err {
-        NogoError::NumArg => {
-            eprintln!("{}", NogoError::NumArg);
+        NogoError::NumArg(_) => {
+            eprintln!("{}", err);
             process::exit(1);
         },
 
@@ -41,23 +39,33 @@ fn match_error(err: &NogoError) {
             process::exit(3);
         },
 
-        NogoError::FailedToOpen => {
-            eprintln!("{}", NogoError::FailedToOpen);
+        NogoError::FailedToOpen(..) => {
+            eprintln!("{}", err);
             process::exit(4);
         },
 
-        NogoError::CorruptFile => {
-            eprintln!("{}", NogoError::CorruptFile);
+        NogoError::CorruptFile { .. } => {
+            eprintln!("{}", err);
             process::exit(5);
         },
 
+        NogoError::BadInput(..) => {
+            eprintln!("{}", err);
+            process::exit(6);
+        },
+
+        NogoError::Internal(_) => {
+            eprintln!("{}", err);
+            process::exit(8);
+        },
+
         NogoError::Parse(_) => {
             eprintln!("{}", NogoError::InvalidDimension);
             process::exit(3);
         },
 
         NogoError::Io(_) => {
-            eprintln!("{}", NogoError::FailedToOpen);
+            eprintln!("{}", err);
             process::exit(4);
         },
     };
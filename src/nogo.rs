@@ -1,25 +1,68 @@
+use std::env;
 use std::fmt;
 use std::io;
+use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
 use std::error::Error;
 use std::num::ParseIntError;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use std::path::Path;
+use std::collections::HashSet;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
 
 use computer::Computer;
+use computer::Difficulty;
+use computer::MAX_STRENGTH;
+use computer::SequenceStart;
+use computer::Strategy;
 
 use game_board::GameBoard;
+use game_board::MoveError;
+use game_board::is_dimension;
+use game_board::SGF_MAX_DIMENSION;
+
+use json;
+use json::Value;
 
 #[derive(Debug)]
 pub enum NogoError {
-    NumArg,
+    // Can have a more specific reason for the bad arguments.
+    NumArg(Option<&'static str>),
     IncorrectType,
     InvalidDimension,
-    FailedToOpen,
-    CorruptFile,
+    // Path of the file that couldn't be opened or created and why.
+    FailedToOpen(String, io::ErrorKind),
+    // line is where in the file the problem is, 0 if it isn't on one line.
+    CorruptFile { line: usize, reason: String },
+    // Bad move read in --batch mode. Has the input line it was on.
+    BadInput(usize, String),
+    // Engine got into a state it shouldn't be able to.
+    Internal(String),
     Parse(ParseIntError),
     Io(io::Error),
 }
 
+impl NogoError {
+    /// CorruptFile for a problem on the given line of a file, 0 if it isn't on
+    /// any one line.
+    pub fn corrupt<S: Into<String>>(line: usize, reason: S) -> NogoError {
+        NogoError::CorruptFile { line, reason: reason.into() }
+    }
+
+    /// FailedToOpen for path, keeping what kind of error e was.
+    pub fn failed_to_open(path: &str, e: &io::Error) -> NogoError {
+        NogoError::FailedToOpen(String::from(path), e.kind())
+    }
+}
+
 impl From<ParseIntError> for NogoError {
     fn from(e: ParseIntError) -> NogoError {
         NogoError::Parse(e)
@@ -32,15 +75,42 @@ impl From<io::Error> for NogoError {
     }
 }
 
+/// Printed after every argument error, flags grouped by what they change.
+const USAGE: &str = "\
+Usage: nogors [flags] p1type p2type [height width [board] | size | small | medium | large | filename]
+       nogors [flags] --analyze file
+Game setup:   [--save file] [--save-on-exit] [--batch] [--strict] [--repro]
+              [--rematch]
+Output:       [--quiet] [--log-size n] [--empty c] [--labels] [--highlight]
+              [--o-name n] [--x-name n] [--o-symbol c] [--x-symbol c]
+              [--sgf file] [--result-format f]
+Computer:     [--strength n] [--difficulty d] [--ai-seed n]
+              [--o-start r,c,m] [--x-start r,c,m] [--explain-ai]
+Timing:       [--timings] [--move-timeout s] [--timeout-rule r]";
+
 impl fmt::Display for NogoError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            NogoError::NumArg           => write!(f, "Usage: nogors p1type p2type \
-                                                      [height width | filename]"),
+            NogoError::NumArg(detail)   => {
+                if let Some(d) = detail {
+                    writeln!(f, "{}", d)?;
+                }
+                write!(f, "{}", USAGE)
+            },
             NogoError::IncorrectType    => write!(f, "Invalid type"),
             NogoError::InvalidDimension => write!(f, "Invalid board dimension"),
-            NogoError::FailedToOpen     => write!(f, "Unable to open file"),
-            NogoError::CorruptFile      => write!(f, "Incorrect file contents"),
+            NogoError::FailedToOpen(ref path, kind) => match kind {
+                io::ErrorKind::NotFound         => write!(f, "Unable to open file: {} (not found)", path),
+                io::ErrorKind::PermissionDenied => write!(f, "Unable to open file: {} (permission denied)", path),
+                _                               => write!(f, "Unable to open file: {}", path),
+            },
+            NogoError::CorruptFile { line: 0, ref reason } =>
+                write!(f, "Incorrect file contents: {}", reason),
+            NogoError::CorruptFile { line, ref reason } =>
+                write!(f, "Incorrect file contents on line {}: {}", line, reason),
+            NogoError::BadInput(line, ref message) =>
+                write!(f, "Invalid move on line {}: {}", line, message),
+            NogoError::Internal(ref message) => write!(f, "Internal error: {}", message),
             NogoError::Parse(ref e)     => write!(f, "Problem parsing: {}", e),
             NogoError::Io(ref e)        => write!(f, "Io failed: {}", e),
         }
@@ -48,25 +118,15 @@ impl fmt::Display for NogoError {
 }
 
 impl Error for NogoError {
-    fn description(&self) -> &str {
-        match *self {
-            NogoError::NumArg           => "program started with incorrect number of arguments",
-            NogoError::IncorrectType    => "incorrect player type",
-            NogoError::InvalidDimension => "board dimension invalid",
-            NogoError::FailedToOpen     => "can't open file for reading",
-            NogoError::CorruptFile      => "bad input in file",
-            NogoError::Parse(ref e)     => e.description(),
-            NogoError::Io(ref e)        => e.description(),
-        }
-    }
-
-    fn cause(&self) -> Option<&Error> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match *self {
-            NogoError::NumArg | 
-            NogoError::IncorrectType | 
-            NogoError::InvalidDimension | 
-            NogoError::FailedToOpen |
-            NogoError::CorruptFile      => None,
+            NogoError::NumArg(_) |
+            NogoError::IncorrectType |
+            NogoError::InvalidDimension |
+            NogoError::FailedToOpen(..) |
+            NogoError::CorruptFile { .. } |
+            NogoError::BadInput(..) |
+            NogoError::Internal(_)      => None,
             NogoError::Parse(ref e)     => Some(e),
             NogoError::Io(ref e)        => Some(e),
         }
@@ -74,7 +134,7 @@ impl Error for NogoError {
 }
 
 /// Used to keep track of current player for output and input.
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Player {
     O,
     X,
@@ -91,350 +151,3049 @@ impl fmt::Display for Player {
     }
 }
 
-#[derive(Debug)]
-pub struct Nogo {
-    // Contains filename from arguments and will contain the name of file user
-    // wants to save to.
-    filename: String,
-
-    // True if argument is a file name. Program needs to load from
-    // a saved file.
-    is_file: bool,
-
-    // True if the user wants to save to a file.
-    is_save: bool,
+/// Why a game ended.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EndReason {
+    Capture,
+    Draw,
+    Abandonment,
+    // A human ran out of time with --timeout-rule lose.
+    Timeout,
+}
 
-    // Player types will either be 'h' or 'c' for human or computer players respectively.
-    // Player 1 is O and player 2 is X.
-    player1_type: char,
-    player2_type: char,
+impl fmt::Display for EndReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EndReason::Capture      => write!(f, "capture"),
+            EndReason::Draw         => write!(f, "draw"),
+            EndReason::Abandonment  => write!(f, "abandonment"),
+            EndReason::Timeout      => write!(f, "timeout"),
+        }
+    }
+}
 
-    // Height and width of game board.
-    height: usize,
-    width:  usize,
+/// Outcome of a finished game. winner is None for draws and abandoned games.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GameResult {
+    pub winner: Option<Player>,
+    pub reason: EndReason,
 }
 
-impl Nogo {
-    /// Initializes game from command line arguments.
-    pub fn new(mut args: ::std::env::Args) -> Result<Nogo, NogoError> {
-        args.next();
-
-        let player1_type = args.next().ok_or(NogoError::NumArg)?;
-        let player2_type = args.next().ok_or(NogoError::NumArg)?;
-        // Temporarily will contain value for height if there is no filename given.
-        let filename     = args.next().ok_or(NogoError::NumArg)?;
-        let arg          = args.next();
-        let mut is_file  = false;
-
-        let width = if arg.is_none() {
-            is_file = true;
-            0
-        } else {
-            arg.unwrap().parse()?
+impl GameResult {
+    /// Returns the result in SGF's RE property format. O plays black and X
+    /// plays white.
+    pub fn to_sgf(&self) -> String {
+        let color = match self.winner {
+            Some(Player::O) => "B",
+            Some(Player::X) => "W",
+            None            => {
+                return match self.reason {
+                    EndReason::Draw => String::from("0"),
+                    _               => String::from("Void"),
+                };
+            },
         };
 
-        let height = if is_file {
-            0
-        } else {
-            filename.trim().parse()?
+        let reason = match self.reason {
+            EndReason::Capture      => "",
+            EndReason::Draw         => "",
+            EndReason::Abandonment  => "F",
+            EndReason::Timeout      => "T",
         };
 
-        // Too many arguments.
-        if args.next().is_some() {
-            return Err(NogoError::NumArg);
-        }
-
-        Ok(Nogo {
-            filename: filename,
-            is_file:  is_file,
-            is_save: false,
-
-            player1_type: match &*player1_type {
-                "h" => 'h',
-                "c" => 'c',
-                 _  => { return Err(NogoError::IncorrectType); },
-            },
+        format!("{}+{}", color, reason)
+    }
 
-            player2_type: match &*player2_type {
-                "h" => 'h',
-                "c" => 'c',
-                 _  => { return Err(NogoError::IncorrectType); },
-            },
+    /// Fills in {winner}, {loser}, and {reason} in format. winner and loser
+    /// are "none" when nobody won.
+    pub fn format(&self, format: &str) -> String {
+        let (winner, loser) = match self.winner {
+            Some(Player::O) => ("O", "X"),
+            Some(Player::X) => ("X", "O"),
+            None            => ("none", "none"),
+        };
 
-            height: height,
-            width:  width,
-        })
+        format.replace("{winner}", winner)
+              .replace("{loser}", loser)
+              .replace("{reason}", &self.reason.to_string())
     }
+}
 
-    /// Loads from save file if given then runs game logic.
-    pub fn run(&mut self) -> Result<(), NogoError> {
-        let mut board;
-        let mut current_player;
-        let mut computer1;
-        let mut computer2;
+/// What a call to Nogo::run ended with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameOutcome {
+    Winner(Player),
+    Draw,
+    // Stopped before anyone won, or only analyzed.
+    Aborted,
+}
 
-        if self.is_file {   // Load from file.
-            let mut file     = File::open(&self.filename)?;
-            let mut contents = String::new();
-
-            file.read_to_string(&mut contents)?;
-            let contents = contents.split_at(contents.find('\n').ok_or(NogoError::CorruptFile)?);
-
-            computer1 = Computer::load(self, contents.0, Player::O)?;
-            computer2 = Computer::load(self, contents.0, Player::X)?;
-            board     = GameBoard::from(contents.1)?;
-
-            let mut first_three = contents.0.split_whitespace().take(3);
-            let height = first_three.next().ok_or(NogoError::CorruptFile)?;
-            let width = first_three.next().ok_or(NogoError::CorruptFile)?;
-            current_player = match first_three.next().ok_or(NogoError::CorruptFile)? {
-                "0" => Player::O,
-                "1" => Player::X,
-                 _  => return Err(NogoError::CorruptFile),
-            };
+impl From<GameResult> for GameOutcome {
+    fn from(result: GameResult) -> GameOutcome {
+        match (result.winner, result.reason) {
+            (Some(winner), _)       => GameOutcome::Winner(winner),
+            (None, EndReason::Draw) => GameOutcome::Draw,
+            (None, _)               => GameOutcome::Aborted,
+        }
+    }
+}
 
-            self.height = height.parse()?;
-            self.width  = width.parse()?;
+/// Games won by each player and drawn over a session of --rematch games.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Scoreboard {
+    pub o_wins: usize,
+    pub x_wins: usize,
+    pub draws:  usize,
+}
 
-            // Make sure height and width from first 2 numbers in file match the
-            // height and width the board got from file.
-            if board.get_height() != self.height || board.get_width() != self.width {
-                return Err(NogoError::CorruptFile);
-            }
-        } else {    // Default. Load from args.
-            computer1       = Computer::new(self, Player::O);
-            computer2       = Computer::new(self, Player::X);
-            board           = GameBoard::new(self.height, self.width)?;
-            current_player  = Player::O;
+impl Scoreboard {
+    /// Counts a finished game. Aborted games don't count.
+    pub fn record(&mut self, outcome: GameOutcome) {
+        match outcome {
+            GameOutcome::Winner(Player::O)  => self.o_wins += 1,
+            GameOutcome::Winner(Player::X)  => self.x_wins += 1,
+            GameOutcome::Draw               => self.draws += 1,
+            GameOutcome::Aborted            => {},
         }
+    }
+}
 
-        loop {
-            board.print();
+/// Order that a human player's two input numbers are read in.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CoordOrder {
+    RowFirst,
+    ColumnFirst,
+}
 
-            let (h, w) = self.get_move(computer1.as_mut(), computer2.as_mut(), &current_player);
-            
-            if self.is_save {
-                match self.save(&board, computer1.as_ref(), computer2.as_ref(), &current_player) {
-                    Ok(_)  => {
-                        self.is_save = false;
-                        continue;     // Don't change player or try to place move.
-                    },
-                    Err(_) => {
-                        eprintln!("Failed to save file");
-                        self.is_save = false;
-                        continue;
-                    },
-                };
-            }
+impl fmt::Display for CoordOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CoordOrder::RowFirst    => write!(f, "row column"),
+            CoordOrder::ColumnFirst => write!(f, "column row"),
+        }
+    }
+}
 
-            if let Err(e) = board.insert_move(h, w, &current_player) {
-                eprintln!("{}", e);
-                continue;
-            }
+/// What a player chose to do on their turn.
+#[derive(Debug, PartialEq)]
+enum Move {
+    Place(usize, usize),
+    // Skip the turn. Two in a row is a draw.
+    Pass,
+    // Take back moves until it is a human's turn again.
+    Undo,
+    // Player left the game or input ran out.
+    Quit,
+    // Player ran out of time with --timeout-rule lose.
+    OutOfTime,
+}
 
-            if let Some((h, w)) = board.check_win() {
-                board.print();
-                let winner = match board.get(h, w) {
-                    'O' => 'X',
-                    'X' => 'O',
-                     _  => '.',   // This should never happen.
-                };
-                assert_ne!(winner, '.');
-                println!("Player {} wins!", winner);
-                break;
-            }
+/// What happens to a human who runs out of time with --move-timeout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TimeoutRule {
+    // Their turn is passed.
+    Pass,
+    // They lose the game.
+    Lose,
+}
 
-            Nogo::change_player(&mut current_player);
+impl TimeoutRule {
+    /// Rule called name on the command line.
+    pub fn from_name(name: &str) -> Option<TimeoutRule> {
+        match name {
+            "pass" => Some(TimeoutRule::Pass),
+            "lose" => Some(TimeoutRule::Lose),
+            _      => None,
         }
-
-        Ok(())
     }
+}
 
-    /// Save current game state to file given from user.
-    fn save(&self,
-            board: &GameBoard, 
-            c1: Option<&Computer>, 
-            c2: Option<&Computer>, 
-            player: &Player) -> Result<(), Box<Error>> {
-
-        let mut file = File::create(&self.filename)?;
+/// Input read line by line on a worker thread so a read can give up. A read
+/// that waits longer than timeout for a line fails with TimedOut, since
+/// stdin can't time out itself.
+struct TimedInput {
+    lines:      Receiver<String>,
+    timeout:    Duration,
+    // Line being read and how much of it has been.
+    line:       Vec<u8>,
+    used:       usize,
+}
 
-        // 0 means O is next to play. 1 means X is next to play.
-        let next_to_play = match *player {
-            Player::O => 0,
-            Player::X => 1,
-        };
+impl TimedInput {
+    fn new(lines: Receiver<String>, timeout: Duration) -> TimedInput {
+        TimedInput { lines, timeout, line: Vec::new(), used: 0 }
+    }
 
-        let c1_row;
-        let c1_column;
-        let c1_counter;
-        match c1 {
-            Some(c) => {
-                c1_row      = c.get_row();
-                c1_column   = c.get_column();
-                c1_counter  = c.get_counter();
-            },
-            None => {
-                c1_row      = 0;
-                c1_column   = 0;
-                c1_counter  = 0;
-            },
-        }
+    /// Starts a thread passing on input's lines until it runs out.
+    fn spawn<R: BufRead + Send + 'static>(mut input: R, timeout: Duration) -> TimedInput {
+        let (sender, lines) = mpsc::channel();
 
-        let c2_row;
-        let c2_column;
-        let c2_counter;
-        match c2 {
-            Some(c) => {
-                c2_row      = c.get_row();
-                c2_column   = c.get_column();
-                c2_counter  = c.get_counter();
-            },
-            None => {
-                c2_row      = 0;
-                c2_column   = 0;
-                c2_counter  = 0;
-            },
-        }
+        thread::spawn(move || loop {
+            let mut line = String::new();
+            match input.read_line(&mut line) {
+                Ok(0) | Err(_)  => break,
+                Ok(_)           => if sender.send(line).is_err() {
+                    break;
+                },
+            }
+        });
 
-        writeln!(file, "{} {} {} {} {} {} {} {} {}", 
-                 self.height,   self.width,     next_to_play,
-                 c1_row,        c1_column,      c1_counter,
-                 c2_row,        c2_column,      c2_counter)?;
+        TimedInput::new(lines, timeout)
+    }
+}
 
-        board.save(&self.filename)?;
+impl Read for TimedInput {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = {
+            let available = self.fill_buf()?;
+            let n = available.len().min(buf.len());
+            buf[..n].copy_from_slice(&available[..n]);
+            n
+        };
+        self.consume(n);
 
-        Ok(())
+        Ok(n)
     }
+}
 
-    pub fn get_p1type(&self) -> char {
-        self.player1_type
-    }
+impl BufRead for TimedInput {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.used == self.line.len() {
+            match self.lines.recv_timeout(self.timeout) {
+                Ok(line)                            => {
+                    self.line = line.into_bytes();
+                    self.used = 0;
+                },
+                Err(RecvTimeoutError::Timeout)      => return Err(io::Error::new(io::ErrorKind::TimedOut, "out of time")),
+                // Nothing left, so the empty rest of the last line is the end.
+                Err(RecvTimeoutError::Disconnected) => {},
+            }
+        }
 
-    pub fn get_p2type(&self) -> char {
-        self.player2_type
+        Ok(&self.line[self.used..])
     }
 
-    pub fn get_height(&self) -> usize {
-        self.height
+    fn consume(&mut self, amount: usize) {
+        self.used += amount;
     }
+}
 
-    pub fn get_width(&self) -> usize {
-        self.width
+/// Wall-clock time spent in each phase of a game.
+#[derive(Debug, Default)]
+struct Timings {
+    input:      Duration,
+    computer:   Duration,
+    win_check:  Duration,
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Timings:")?;
+        writeln!(f, "    input:      {:.3}s", self.input.as_secs_f64())?;
+        writeln!(f, "    computer:   {:.3}s", self.computer.as_secs_f64())?;
+        write!(f,   "    win check:  {:.3}s", self.win_check.as_secs_f64())
     }
+}
 
-    /// Gets move from computer or player. Saves current game to specified
-    /// file from user.
-    fn get_move(&mut self, 
-                c1: Option<&mut Computer>, 
-                c2: Option<&mut Computer>, 
-                player: &Player) 
-        -> (usize, usize) 
-    {
-        print!("Player {}> ", player);
-        io::stdout().flush().unwrap();
+/// Where the board of a new game comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardSource {
+    Dimensions { height: usize, width: usize },
+    File(String),
+}
 
-        let computer = match *player {
-            Player::O => c1,
-            Player::X => c2,
-        };
+/// Command line arguments after they have been checked, ready for Nogo::new.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Player types, 'h' or 'c'. Player 1 is O and player 2 is X.
+    pub p1: char,
+    pub p2: char,
+
+    /// Where the board comes from.
+    pub source: BoardSource,
+
+    /// Cells of the board to start from, only with Dimensions.
+    pub seed: Option<String>,
+
+    // Flags, see the fields of Nogo they end up in.
+    pub timings:        bool,
+    pub autosave:       Option<String>,
+    pub save_on_exit:   bool,
+    pub batch:          bool,
+    pub quiet:          bool,
+    pub rematch:        bool,
+    pub move_timeout:   Option<Duration>,
+    pub timeout_rule:   TimeoutRule,
+    pub log_size:       usize,
+    pub strict:         bool,
+    pub empty_glyph:    char,
+    pub names:          [Option<String>; 2],
+    pub symbols:        [char; 2],
+    pub labels:         bool,
+    pub highlight:      bool,
+    pub analyze:        bool,
+    pub repro:          bool,
+    pub strength:       u8,
+    pub difficulty:     Option<Difficulty>,
+    pub starts:         [Option<SequenceStart>; 2],
+    pub ai_seed:        usize,
+    pub explain_ai:     bool,
+    pub result_format:  Option<String>,
+    pub sgf:            Option<String>,
+}
 
-        if computer.is_some() {
-            let (h, w) = computer.unwrap().get_and_generate_move();
-            println!("{} {}", h, w);
-            return (h, w);
+impl Default for Config {
+    /// Two humans on the small board, no flags.
+    fn default() -> Config {
+        Config {
+            p1:             'h',
+            p2:             'h',
+            source:         BoardSource::Dimensions { height: 7, width: 7 },
+            seed:           None,
+            timings:        false,
+            autosave:       None,
+            save_on_exit:   false,
+            batch:          false,
+            quiet:          false,
+            rematch:        false,
+            move_timeout:   None,
+            timeout_rule:   TimeoutRule::Pass,
+            log_size:       DEFAULT_LOG_SIZE,
+            strict:         false,
+            empty_glyph:    '.',
+            names:          [None, None],
+            symbols:        ['O', 'X'],
+            labels:         false,
+            highlight:      false,
+            analyze:        false,
+            repro:          false,
+            strength:       0,
+            difficulty:     None,
+            starts:         [None, None],
+            ai_seed:        0,
+            explain_ai:     false,
+            result_format:  None,
+            sgf:            None,
         }
+    }
+}
 
-        loop {
-            let input = match Nogo::get_player_move() {
-                Ok(s)  => s,
-                Err(e) => {
-                    eprintln!("Error: {}", e);
-                    print!("Player {}> ", player);
-                    io::stdout().flush().unwrap();
-                    continue;
+impl Config {
+    /// Parses command line arguments. The first one is the program name and
+    /// is skipped.
+    pub fn parse<I: Iterator<Item = String>>(args: I) -> Result<Config, NogoError> {
+        let mut config     = Config::default();
+        let mut analyze    = None;
+        let mut positional = Vec::new();
+
+        let mut args = args.skip(1);
+        while let Some(arg) = args.next() {
+            match &*arg {
+                "--timings" => config.timings = true,
+                "--save"    => config.autosave = Some(args.next().ok_or(NogoError::NumArg(None))?),
+                "--save-on-exit" => config.save_on_exit = true,
+                "--batch"   => config.batch = true,
+                "--quiet"   => config.quiet = true,
+                "--rematch" => config.rematch = true,
+                "--move-timeout" => {
+                    let seconds = number(args.next(), "Invalid --move-timeout")?;
+                    if seconds == 0 {
+                        return Err(NogoError::NumArg(Some("Invalid --move-timeout")));
+                    }
+                    config.move_timeout = Some(Duration::from_secs(seconds));
                 },
-            };
+                "--timeout-rule" => {
+                    let name = args.next().ok_or(NogoError::NumArg(None))?;
+                    config.timeout_rule = TimeoutRule::from_name(&name).ok_or(NogoError::NumArg(None))?;
+                },
+                "--log-size" => config.log_size = number(args.next(), "Invalid --log-size")?,
+                "--strict"  => config.strict = true,
+                "--empty"   => {
+                    config.empty_glyph = glyph(args.next())?;
+                    if config.empty_glyph == 'O' || config.empty_glyph == 'X' {
+                        return Err(NogoError::NumArg(None));
+                    }
+                },
+                "--o-name" | "--x-name" => {
+                    let index = if arg == "--o-name" { 0 } else { 1 };
+                    config.names[index] = Some(args.next().ok_or(NogoError::NumArg(None))?);
+                },
+                "--o-symbol" | "--x-symbol" => {
+                    let index = if arg == "--o-symbol" { 0 } else { 1 };
+                    config.symbols[index] = glyph(args.next())?;
+                },
+                "--labels"  => config.labels = true,
+                "--highlight" => config.highlight = true,
+                "--analyze" => analyze = Some(args.next().ok_or(NogoError::NumArg(None))?),
+                "--repro"   => config.repro = true,
+                "--strength" => {
+                    config.strength = match args.next().ok_or(NogoError::NumArg(None))?.parse() {
+                        Ok(n) if n <= MAX_STRENGTH => n,
+                        _ => return Err(NogoError::NumArg(Some("Invalid --strength"))),
+                    };
+                },
+                "--ai-seed"  => config.ai_seed = number(args.next(), "Invalid --ai-seed")?,
+                "--difficulty" => {
+                    let name = args.next().ok_or(NogoError::NumArg(None))?;
+                    config.difficulty = Some(Difficulty::from_name(&name).ok_or(NogoError::NumArg(None))?);
+                },
+                "--explain-ai" => config.explain_ai = true,
+                "--result-format" => config.result_format = Some(args.next().ok_or(NogoError::NumArg(None))?),
+                "--o-start" | "--x-start" => {
+                    let start = args.next().ok_or(NogoError::NumArg(None))?;
+                    let index = if arg == "--o-start" { 0 } else { 1 };
+                    config.starts[index] = Some(SequenceStart::parse(&start).ok_or(NogoError::NumArg(None))?);
+                },
+                "--sgf"     => config.sgf = Some(args.next().ok_or(NogoError::NumArg(None))?),
+                a if a.starts_with("--") => return Err(NogoError::NumArg(None)),
+                _ => positional.push(arg),
+            }
+        }
 
-            if input.0 == "w" {
-                println!("Saving to {}", input.1);
-                self.filename = input.1;
-                self.is_save = true;
-                return (0, 0);  // Leave function to go save.
+        // Stones have to look different from each other, empty cells, and the
+        // board's border.
+        let symbols = config.symbols;
+        if symbols[0] == symbols[1] || symbols.contains(&config.empty_glyph)
+                                    || symbols.iter().any(|s| "/\\|-".contains(*s)) {
+            return Err(NogoError::NumArg(None));
+        }
+
+        // --highlight shows the last stone in lowercase, so each symbol needs
+        // a lowercase form nothing else on the board uses.
+        if config.highlight && symbols.iter().any(|s| !s.is_ascii_uppercase() || s.to_ascii_lowercase() == config.empty_glyph) {
+            return Err(NogoError::NumArg(None));
+        }
+
+        // Analyzing loads the file like a game between two humans.
+        if let Some(path) = analyze {
+            if !positional.is_empty() {
+                return Err(NogoError::NumArg(None));
             }
+            config.analyze = true;
+            positional = vec![String::from("h"), String::from("h"), path];
+        }
 
-            let h = match input.0.parse() {
-                Ok(u)  => u,
-                Err(e) => {
-                    eprintln!("Error: {}", e);
-                    print!("Player {}> ", player);
-                    io::stdout().flush().unwrap();
-                    continue;
-                },
-            };
+        let mut args = positional.into_iter();
 
-            let w = match input.1.parse() {
-                Ok(u)  => u,
-                Err(e) => {
-                    eprintln!("Error: {}", e);
-                    print!("Player {}> ", player);
-                    io::stdout().flush().unwrap();
-                    continue;
-                },
-            };
+        config.p1 = player_type(&args.next().ok_or(NogoError::NumArg(None))?)?;
+        config.p2 = player_type(&args.next().ok_or(NogoError::NumArg(None))?)?;
+        // Height, a square board's size, a board preset or a save file.
+        let name  = args.next().ok_or(
+            NogoError::NumArg(Some("Missing board dimensions or save file")))?;
+
+        config.source = match args.next() {
+            Some(width) => BoardSource::Dimensions {
+                height: name.trim().parse()?,
+                width:  width.parse()?,
+            },
+            None        => match (preset(&name), name.trim().parse()) {
+                (Some((height, width)), _) => BoardSource::Dimensions { height, width },
+                (None, Ok(size))           => BoardSource::Dimensions { height: size, width: size },
+                (None, Err(_))             => BoardSource::File(name),
+            },
+        };
+
+        if let BoardSource::Dimensions { .. } = config.source {
+            config.seed = args.next();
+        }
 
-            return (h, w);
+        // A loaded board's size isn't known yet, so that is checked when the
+        // SGF is written.
+        if let (Some(_), &BoardSource::Dimensions { height, width }) = (config.sgf.as_ref(), &config.source) {
+            if height > SGF_MAX_DIMENSION || width > SGF_MAX_DIMENSION {
+                return Err(NogoError::InvalidDimension);
+            }
+        }
+
+        // Too many arguments.
+        if args.next().is_some() {
+            return Err(NogoError::NumArg(None));
         }
+
+        Ok(config)
     }
+}
 
-    /// Gets player move from standard input. Returns input as tuple.
-    fn get_player_move() -> Result<(String, String), Box<Error>> {
-        let mut buffer = String::new();
+/// Sets up a game without going through command line arguments. Players are
+/// human unless set otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct NogoBuilder {
+    p1:     Option<char>,
+    p2:     Option<char>,
+    source: Option<BoardSource>,
+}
 
-        io::stdin().read_line(&mut buffer)?;
+impl NogoBuilder {
+    pub fn new() -> NogoBuilder {
+        NogoBuilder::default()
+    }
 
-        let input: Vec<&str> = buffer.split_whitespace().collect();
+    /// Type of player 1 (O), 'h' or 'c'.
+    pub fn player1(mut self, p: char) -> NogoBuilder {
+        self.p1 = Some(p);
+        self
+    }
 
-        let h = match input.get(0) {
-            Some(n) => String::from(*n),
-            None    => return Err(From::from("please enter 2 numbers")),
-        };
+    /// Type of player 2 (X), 'h' or 'c'.
+    pub fn player2(mut self, p: char) -> NogoBuilder {
+        self.p2 = Some(p);
+        self
+    }
 
-        let w = match input.get(1) {
-            Some(n) => String::from(*n),
-            None    => return Err(From::from("please enter 2 numbers")),
-        };
+    /// Starts on an empty board. Replaces any file given before.
+    pub fn dimensions(mut self, height: usize, width: usize) -> NogoBuilder {
+        self.source = Some(BoardSource::Dimensions { height, width });
+        self
+    }
 
-        Ok((h, w))
+    /// Loads a saved game. Replaces any dimensions given before.
+    pub fn from_file(mut self, filename: String) -> NogoBuilder {
+        self.source = Some(BoardSource::File(filename));
+        self
     }
 
-    /// Change current player to next player.
-    fn change_player(current_player: &mut Player) {
-        *current_player = match *current_player {
-            Player::O => Player::X,
-            Player::X => Player::O,
+    /// Checks the settings and makes the game.
+    pub fn build(self) -> Result<Nogo, NogoError> {
+        let p1 = player_type(&self.p1.unwrap_or('h').to_string())?;
+        let p2 = player_type(&self.p2.unwrap_or('h').to_string())?;
+
+        let source = self.source.ok_or(
+            NogoError::NumArg(Some("Missing board dimensions or save file")))?;
+
+        if let BoardSource::Dimensions { height, width } = source {
+            if !is_dimension(height) || !is_dimension(width) {
+                return Err(NogoError::InvalidDimension);
+            }
         }
+
+        Nogo::new(Config { p1, p2, source, ..Config::default() })
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// Returns the rest of a save file's first line after checking it starts
+/// with SAVE_FORMAT_TAG.
+pub fn save_header(line: &str) -> Result<&str, NogoError> {
+    match line.strip_prefix(SAVE_FORMAT_TAG) {
+        // "nogo v10" isn't version 1.
+        Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => Ok(rest),
+        _ => Err(NogoError::corrupt(1, format!("header doesn't start with \"{}\"", SAVE_FORMAT_TAG))),
+    }
+}
 
-    #[test]
-    fn test_change_player() {
-        let mut current_player = Player::O;
+/// Calls write with the path of a temp file next to filename then renames the
+/// temp file over filename. If write fails filename is left untouched.
+fn write_atomic<E, F>(filename: &str, write: F) -> Result<(), E>
+    where E: From<io::Error>,
+          F: FnOnce(&str) -> Result<(), E>
+{
+    let temp = format!("{}.tmp", filename);
+
+    let result = write(&temp).and_then(|_| fs::rename(&temp, filename).map_err(E::from));
+    if result.is_err() {
+        // Might not have been created.
+        let _ = fs::remove_file(&temp);
+    }
 
-        match current_player {
-            Player::O => assert!(true),
-            Player::X => panic!("not equal"),
-        }
+    result
+}
 
-        Nogo::change_player(&mut current_player);
+/// Returns 'h' or 'c' for a player type argument.
+fn player_type(arg: &str) -> Result<char, NogoError> {
+    match arg {
+        "h" => Ok('h'),
+        "c" => Ok('c'),
+         _  => Err(NogoError::IncorrectType),
+    }
+}
+
+/// Returns the one character in arg, which can't be whitespace.
+fn glyph(arg: Option<String>) -> Result<char, NogoError> {
+    let arg   = arg.ok_or(NogoError::NumArg(None))?;
+    let chars = arg.chars().collect::<Vec<char>>();
+
+    match chars[..] {
+        [ch] if !ch.is_whitespace() => Ok(ch),
+        _                           => Err(NogoError::NumArg(None)),
+    }
+}
+
+/// Returns the whole number in arg. One that doesn't parse is an error with
+/// the given message, like "Invalid --log-size".
+fn number<T: FromStr>(arg: Option<String>, message: &'static str) -> Result<T, NogoError> {
+    arg.ok_or(NogoError::NumArg(None))?.parse().map_err(|_| NogoError::NumArg(Some(message)))
+}
+
+/// Returns height and width of a named board size.
+fn preset(name: &str) -> Option<(usize, usize)> {
+    match name {
+        "small"  => Some((7, 7)),
+        "medium" => Some((13, 13)),
+        "large"  => Some((19, 19)),
+        _        => None,
+    }
+}
+
+#[derive(Debug)]
+pub struct Nogo {
+    // Contains filename from arguments and will contain the name of file user
+    // wants to save to.
+    filename: String,
+
+    // True if argument is a file name. Program needs to load from
+    // a saved file.
+    is_file: bool,
+
+    // True if the user wants to save to a file.
+    is_save: bool,
+
+    // Player types will either be 'h' or 'c' for human or computer players respectively.
+    // Player 1 is O and player 2 is X.
+    player1_type: char,
+    player2_type: char,
+
+    // Height and width of game board.
+    height: usize,
+    width:  usize,
+
+    // Order human players enter coordinates in. Can be flipped during the game
+    // with the "order" command.
+    coord_order: CoordOrder,
+
+    // Only collected when started with --timings.
+    timings: Option<Timings>,
+
+    // File the game is saved to after every move. Set with --save.
+    autosave: Option<String>,
+
+    // File the game is saved to when it is abandoned. Set with --save-on-exit,
+    // uses the --save file if there is one otherwise DEFAULT_EXIT_SAVE.
+    exit_save: Option<String>,
+
+    // True when moves are piped in. Bad input ends the game with an error
+    // instead of asking again. Set with --batch.
+    batch: bool,
+
+    // True if boards, prompts, and computer moves aren't printed, only how
+    // the game ended. Set with --quiet.
+    quiet: bool,
+
+    // True if players are asked to play again after each game. Set with
+    // --rematch.
+    rematch: bool,
+
+    // Wins and draws of the games played so far with --rematch.
+    scoreboard: Scoreboard,
+
+    // Longest a human may take over a move when reading standard input.
+    // Set with --move-timeout.
+    move_timeout: Option<Duration>,
+
+    // What happens to a human who takes longer. Set with --timeout-rule.
+    timeout_rule: TimeoutRule,
+
+    // Number of the move about to be played, shown in the prompt. A loaded
+    // game starts from its number of stones.
+    move_number: usize,
+
+    // Number of lines read from input so far.
+    lines_read: usize,
+
+    // Number of moves the "log" command shows. Set with --log-size.
+    log_size: usize,
+
+    // True if loaded games are checked for captures that should have ended
+    // the game earlier. Set with --strict.
+    strict: bool,
+
+    // Character empty cells are shown as. Set with --empty.
+    empty_glyph: char,
+
+    // Names and characters players are shown with in place of "Player O"
+    // and "Player X", O's first. Saves still use O and X. Set with --o-name,
+    // --x-name, --o-symbol, and --x-symbol.
+    names:   [Option<String>; 2],
+    symbols: [char; 2],
+
+    // True if row and column numbers are shown around the board. Set with
+    // --labels.
+    labels: bool,
+
+    // True if the stone placed last is shown in lowercase. Set with
+    // --highlight.
+    highlight: bool,
+
+    // Cells of the board to start from, row after row, if given after the
+    // height and width.
+    seed: Option<String>,
+
+    // True if the saved game should be described instead of played. Set with
+    // --analyze.
+    analyze: bool,
+
+    // Directory a repro file is written to when the engine hits an internal
+    // error. Set with --repro.
+    repro_dir: Option<String>,
+
+    // How carefully computer players pick moves. Set with --strength.
+    strength: u8,
+
+    // Preset used by computer players in place of strength. Set with
+    // --difficulty.
+    difficulty: Option<Difficulty>,
+
+    // Where each computer's move sequence starts in place of the usual one,
+    // O's first. Set with --o-start and --x-start and kept in save files.
+    sequence_starts: [Option<SequenceStart>; 2],
+
+    // Shuffles the order of the moves between each computer's jumps, 0 for
+    // the usual order. Set with --ai-seed and kept in save files.
+    ai_seed: usize,
+
+    // True if computers say why they chose each move. Set with --explain-ai.
+    explain_ai: bool,
+
+    // Format of the line printed when the game ends, filled in by
+    // GameResult::format. Set with --result-format.
+    result_format: Option<String>,
+
+    // File the game is written to as SGF when it ends, however it ends. Set
+    // with --sgf.
+    sgf: Option<String>,
+
+    // Strategies computer players use in place of DeterministicStrategy. Set
+    // with set_strategy.
+    strategy1: Option<Box<dyn Strategy>>,
+    strategy2: Option<Box<dyn Strategy>>,
+}
+
+/// Number of moves the "log" command shows by default.
+const DEFAULT_LOG_SIZE: usize = 10;
+
+/// Tag the first line of a save file starts with. The version goes up when
+/// the format changes so old files aren't misread.
+pub const SAVE_FORMAT_TAG: &str = "nogo v1";
+
+/// File an abandoned game is saved to when --save-on-exit is given without --save.
+const DEFAULT_EXIT_SAVE: &str = "autosave.txt";
+
+/// Terminal width assumed when COLUMNS isn't set.
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+
+impl Nogo {
+    /// Initializes game from command line arguments.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Nogo, NogoError> {
+        Nogo::new(Config::parse(args.into_iter())?)
+    }
+
+    /// Initializes game from already parsed arguments.
+    pub fn new(config: Config) -> Result<Nogo, NogoError> {
+        let (filename, is_file, height, width) = match config.source {
+            BoardSource::Dimensions { height, width } => (String::new(), false, height, width),
+            BoardSource::File(path)                   => (path, true, 0, 0),
+        };
+
+        let nogo = Nogo {
+            filename,
+            is_file,
+            is_save: false,
+
+            player1_type: config.p1,
+            player2_type: config.p2,
+
+            height,
+            width,
+
+            coord_order: CoordOrder::RowFirst,
+            timings: if config.timings { Some(Timings::default()) } else { None },
+            exit_save: if config.save_on_exit {
+                Some(config.autosave.clone().unwrap_or_else(|| String::from(DEFAULT_EXIT_SAVE)))
+            } else {
+                None
+            },
+            autosave: config.autosave,
+            batch: config.batch,
+            quiet: config.quiet,
+            rematch: config.rematch,
+            scoreboard: Scoreboard::default(),
+            move_timeout: config.move_timeout,
+            timeout_rule: config.timeout_rule,
+            move_number: 1,
+            lines_read: 0,
+            log_size: config.log_size,
+            strict: config.strict,
+            empty_glyph: config.empty_glyph,
+            names: config.names,
+            symbols: config.symbols,
+            labels: config.labels,
+            highlight: config.highlight,
+            seed: config.seed,
+            analyze: config.analyze,
+            repro_dir: if config.repro { Some(String::from(".")) } else { None },
+            strength: config.strength,
+            difficulty: config.difficulty,
+            sequence_starts: config.starts,
+            ai_seed: config.ai_seed,
+            explain_ai: config.explain_ai,
+            result_format: config.result_format,
+            sgf: config.sgf,
+            strategy1: None,
+            strategy2: None,
+        };
+
+        // Make sure a starting board is usable before the game begins.
+        if nogo.seed.is_some() {
+            nogo.start_board()?;
+        }
+
+        Ok(nogo)
+    }
+
+    /// Board a new game starts on and the player who goes first. Empty with O
+    /// first unless a starting board was given, then whoever has fewer stones
+    /// goes first.
+    fn start_board(&self) -> Result<(GameBoard, Player), NogoError> {
+        let cells = match self.seed {
+            Some(ref cells) => cells,
+            None            => return Ok((GameBoard::new(self.height, self.width)?, Player::O)),
+        };
+
+        let board = GameBoard::from_flat(self.height, self.width, cells)?;
+
+        // Can't start from a game that is already over.
+        if board.try_check_win()?.is_some() {
+            return Err(NogoError::corrupt(0, "board is already decided"));
+        }
+
+        let first = if board.stone_count(&Player::O) > board.stone_count(&Player::X) {
+            Player::X
+        } else {
+            Player::O
+        };
+
+        Ok((board, first))
+    }
+
+    /// Loads from save file if given then runs game logic. Moves are read
+    /// from standard input. How the game ended is printed as well as
+    /// returned.
+    pub fn run(&mut self) -> Result<GameOutcome, NogoError> {
+        if let Some(timeout) = self.move_timeout {
+            let mut input = TimedInput::spawn(io::BufReader::new(io::stdin()), timeout);
+            return self.run_with(&mut input);
+        }
+
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+
+        self.run_with(&mut input)
+    }
+
+    /// Same as run but human moves are read from input. A read from input
+    /// failing with TimedOut means the human ran out of time.
+    pub fn run_with<R: BufRead>(&mut self, input: &mut R) -> Result<GameOutcome, NogoError> {
+        let mut board;
+        let mut current_player;
+        let mut computer1;
+        let mut computer2;
+
+        if self.is_file {   // Load from file.
+            let filename = self.filename.clone();
+            let loaded   = if filename.ends_with(".sgf") {
+                self.load_sgf(&filename)?
+            } else {
+                self.load(&filename)?
+            };
+
+            board           = loaded.0;
+            computer1       = loaded.1;
+            computer2       = loaded.2;
+            current_player  = loaded.3;
+        } else {    // Default. Load from args.
+            let (start, first) = self.start_board()?;
+
+            computer1       = Computer::new(self, Player::O);
+            computer2       = Computer::new(self, Player::X);
+            board           = start;
+            current_player  = first;
+        }
+
+        // Computers playing a set_strategy strategy keep it for rematches.
+        let custom = (computer1.is_some() && self.strategy1.is_some(),
+                      computer2.is_some() && self.strategy2.is_some());
+        if let (Some(_), Some(strategy)) = (computer1.as_ref(), self.strategy1.take()) {
+            computer1 = Some(Computer::with_strategy(strategy));
+        }
+        if let (Some(_), Some(strategy)) = (computer2.as_ref(), self.strategy2.take()) {
+            computer2 = Some(Computer::with_strategy(strategy));
+        }
+
+        if let Some(warning) = Nogo::width_warning(board.get_width(), Nogo::terminal_width()) {
+            eprintln!("{}", warning);
+        }
+
+        if self.analyze {
+            print!("{}", self.render(&board));
+            println!("{}", Nogo::analysis(&board, &current_player)?);
+            return Ok(GameOutcome::Aborted);
+        }
+
+        // A loaded game may already be over.
+        if let Some((h, w)) = board.try_check_win()? {
+            return self.announce_win(&board, h, w).map(GameOutcome::from);
+        }
+
+        loop {
+            let result = self.play(input, &mut board, &mut computer1, &mut computer2, &mut current_player);
+
+            if let (Some(path), Ok(r)) = (self.sgf.as_ref(), result.as_ref()) {
+                let written = write_atomic(path, |temp| -> Result<(), NogoError> {
+                    fs::write(temp, board.to_sgf(Some(r))?)?;
+                    Ok(())
+                });
+                if let Err(e) = written {
+                    eprintln!("Failed to write SGF file: {}", e);
+                }
+            }
+
+            if let Err(NogoError::Internal(_)) = result {
+                if self.repro_dir.is_some() {
+                    match self.write_repro(&board, computer1.as_ref(), computer2.as_ref(), &current_player) {
+                        Ok(path) => eprintln!("Wrote {} for a bug report. Load it with: nogors {} {} {}",
+                                              path, self.player1_type, self.player2_type, path),
+                        Err(_)   => eprintln!("Failed to write repro file"),
+                    }
+                }
+            }
+
+            let outcome = result.map(GameOutcome::from)?;
+            if !self.rematch || outcome == GameOutcome::Aborted {
+                return Ok(outcome);
+            }
+
+            self.scoreboard.record(outcome);
+            println!("{}", self.standings());
+
+            if !self.play_again(input)? {
+                return Ok(outcome);
+            }
+
+            // Same settings on an empty board, computers from the start of
+            // their sequences. Strategies from set_strategy carry on where
+            // they got to.
+            board           = GameBoard::new(self.height, self.width)?;
+            computer1       = match (custom.0, computer1.take()) {
+                (true, Some(c)) => Some(Computer::with_strategy(c.into_strategy())),
+                _               => Computer::new(self, Player::O),
+            };
+            computer2       = match (custom.1, computer2.take()) {
+                (true, Some(c)) => Some(Computer::with_strategy(c.into_strategy())),
+                _               => Computer::new(self, Player::X),
+            };
+            current_player  = Player::O;
+        }
+    }
+
+    /// Wins and draws so far with --rematch, like "Standings: Player O 2,
+    /// Player X 1, draws 0".
+    pub fn standings(&self) -> String {
+        format!("Standings: {} {}, {} {}, draws {}",
+                self.player_name(&Player::O), self.scoreboard.o_wins,
+                self.player_name(&Player::X), self.scoreboard.x_wins,
+                self.scoreboard.draws)
+    }
+
+    pub fn get_scoreboard(&self) -> Scoreboard {
+        self.scoreboard
+    }
+
+    /// Asks whether to play another game. Only "y" or "yes" starts one,
+    /// anything else or the end of input ends the session.
+    fn play_again<R: BufRead>(&mut self, input: &mut R) -> Result<bool, NogoError> {
+        if !self.quiet {
+            print!("Play again? (y/n) ");
+            io::stdout().flush()?;
+        }
+
+        let mut line = String::new();
+        match input.read_line(&mut line) {
+            Ok(0)  => {
+                if !self.quiet {
+                    println!();
+                }
+                return Ok(false);
+            },
+            Ok(_)  => {},
+            // Too slow to answer with --move-timeout.
+            Err(ref e) if e.kind() == io::ErrorKind::TimedOut => return Ok(false),
+            Err(e) => return Err(e.into()),
+        }
+        self.lines_read += 1;
+
+        Ok(matches!(line.trim(), "y" | "yes"))
+    }
+
+    /// Plays moves, one "row column" or "pass" per line, in turn from the
+    /// start of the game without printing anything. Stops at the first win,
+    /// two passes in a row, or the end of moves, which leaves the game
+    /// Aborted. Bad moves are errors with their line number.
+    pub fn replay<R: BufRead>(&mut self, moves: R) -> Result<GameOutcome, NogoError> {
+        let (mut board, mut player) = if self.is_file {
+            let filename = self.filename.clone();
+            let loaded   = self.load(&filename)?;
+            (loaded.0, loaded.3)
+        } else {
+            self.start_board()?
+        };
+        let mut passed = false;
+
+        for (i, line) in moves.lines().enumerate() {
+            let line   = line?;
+            let tokens = line.split('#').next().unwrap_or("").split_whitespace().collect::<Vec<&str>>();
+            let bad    = |message: String| NogoError::BadInput(i + 1, message);
+
+            match tokens[..] {
+                []          => continue,
+                ["pass"]    => {
+                    if passed {
+                        return Ok(GameOutcome::Draw);
+                    }
+                    passed = true;
+                },
+                [row, column] => {
+                    let h = row.parse().map_err(|_| bad(format!("\"{}\" isn't a row", row)))?;
+                    let w = column.parse().map_err(|_| bad(format!("\"{}\" isn't a column", column)))?;
+
+                    board.insert_move(h, w, &player).map_err(|e| match e {
+                        MoveError::GroupTooBig => NogoError::Internal(e.to_string()),
+                        _                      => bad(e.to_string()),
+                    })?;
+                    passed = false;
+
+                    if let Some((h, w)) = board.try_check_win_at(h, w)? {
+                        return Ok(GameOutcome::Winner(Nogo::winner_at(&board, h, w)?));
+                    }
+                },
+                _           => return Err(bad(String::from("expected \"row column\" or \"pass\""))),
+            }
+
+            Nogo::change_player(&mut player);
+        }
+
+        Ok(GameOutcome::Aborted)
+    }
+
+    /// Game loop. Plays moves on board until someone wins or the game is
+    /// abandoned, and returns how it ended.
+    fn play<R: BufRead>(&mut self,
+                        input: &mut R,
+                        board: &mut GameBoard,
+                        computer1: &mut Option<Computer>,
+                        computer2: &mut Option<Computer>,
+                        current_player: &mut Player) -> Result<GameResult, NogoError> {
+        let mut passed = false;
+
+        self.move_number = board.stone_count(&Player::O) + board.stone_count(&Player::X) + 1;
+
+        // Hashes of every position played so far, to warn when one comes
+        // back.
+        let mut seen = HashSet::new();
+        seen.insert(board.position_hash());
+
+        loop {
+            if !self.quiet {
+                print!("{}", self.render(board));
+            }
+
+            let start = Instant::now();
+            let next  = self.get_move(input,
+                                      board,
+                                      computer1.as_mut(),
+                                      computer2.as_mut(),
+                                      current_player)?;
+
+            let is_computer = match current_player {
+                Player::O => computer1.is_some(),
+                Player::X => computer2.is_some(),
+            };
+
+            if let Some(ref mut t) = self.timings {
+                if is_computer {
+                    t.computer += start.elapsed();
+                } else {
+                    t.input += start.elapsed();
+                }
+            }
+
+            let (h, w) = match next {
+                Move::Place(h, w) => (h, w),
+                Move::Pass        => {
+                    if passed {
+                        let draw = GameResult { winner: None, reason: EndReason::Draw };
+                        println!("{}", self.result_line(&draw));
+                        self.print_timings();
+                        return Ok(draw);
+                    }
+                    passed = true;
+
+                    Nogo::change_player(current_player);
+                    self.autosave(board, computer1.as_ref(), computer2.as_ref(), current_player);
+                    continue;
+                },
+                Move::Undo        => {
+                    passed = false;
+
+                    // Positions taken back weren't played as far as repeats go.
+                    let mut undone = board.clone();
+                    self.undo(board, computer1, computer2, current_player);
+                    while undone.get_history().len() > board.get_history().len() {
+                        seen.remove(&undone.position_hash());
+                        undone.undo();
+                        self.move_number -= 1;
+                    }
+                    continue;
+                },
+                Move::Quit        => {
+                    if let Some(ref path) = self.exit_save {
+                        println!("Saving to {}", path);
+
+                        if self.save(path,
+                                     board,
+                                     computer1.as_ref(),
+                                     computer2.as_ref(),
+                                     current_player).is_err() {
+                            eprintln!("Failed to save file");
+                        }
+                    }
+                    self.print_timings();
+                    return Ok(GameResult { winner: None, reason: EndReason::Abandonment });
+                },
+                Move::OutOfTime   => {
+                    let mut winner = *current_player;
+                    Nogo::change_player(&mut winner);
+
+                    let result = GameResult { winner: Some(winner), reason: EndReason::Timeout };
+                    println!("{}", self.result_line(&result));
+                    self.print_timings();
+                    return Ok(result);
+                },
+            };
+            
+            if self.is_save {
+                match self.save(&self.filename,
+                                board,
+                                computer1.as_ref(),
+                                computer2.as_ref(),
+                                current_player) {
+                    Ok(_)  => {
+                        self.is_save = false;
+                        continue;     // Don't change player or try to place move.
+                    },
+                    Err(_) => {
+                        eprintln!("Failed to save file");
+                        self.is_save = false;
+                        continue;
+                    },
+                };
+            }
+
+            if let Err(e) = board.insert_move(h, w, current_player) {
+                if e == MoveError::GroupTooBig {
+                    return Err(NogoError::Internal(e.to_string()));
+                }
+                if self.batch && !is_computer {
+                    return Err(NogoError::BadInput(self.lines_read, e.to_string()));
+                }
+
+                eprintln!("{}", e);
+                continue;
+            }
+            passed = false;
+            self.move_number += 1;
+
+            if !seen.insert(board.position_hash()) {
+                eprintln!("Warning: this position has been played before");
+            }
+
+            let start = Instant::now();
+            let win   = board.try_check_win_at(h, w)?;
+            if let Some(ref mut t) = self.timings {
+                t.win_check += start.elapsed();
+            }
+
+            if let Some((h, w)) = win {
+                return self.announce_win(board, h, w);
+            }
+
+            // Nowhere left to play. Filling the last cell leaves some group
+            // without liberties so this is only a backstop.
+            if board.is_full() {
+                if !self.quiet {
+                    print!("{}", self.render(board));
+                }
+                let draw = GameResult { winner: None, reason: EndReason::Draw };
+                println!("{}", self.result_line(&draw));
+                self.print_timings();
+                return Ok(draw);
+            }
+
+            Nogo::change_player(current_player);
+
+            self.autosave(board, computer1.as_ref(), computer2.as_ref(), current_player);
+        }
+    }
+
+    /// Width of the terminal from the COLUMNS environment variable, or
+    /// DEFAULT_TERMINAL_WIDTH if it isn't set.
+    fn terminal_width() -> usize {
+        env::var("COLUMNS").ok()
+                           .and_then(|c| c.parse().ok())
+                           .filter(|c| *c > 0)
+                           .unwrap_or(DEFAULT_TERMINAL_WIDTH)
+    }
+
+    /// Warning to show if a board this wide, plus its border, won't fit on
+    /// one line of the terminal.
+    fn width_warning(board_width: usize, terminal_width: usize) -> Option<String> {
+        if board_width + 2 <= terminal_width {
+            return None;
+        }
+
+        Some(format!("Warning: the board is {} columns wide with its border but the terminal \
+                      is {}, so rows will wrap. Try a smaller board or a wider terminal.",
+                     board_width + 2, terminal_width))
+    }
+
+    /// Board as it is shown to players, with the --empty, --labels, and
+    /// --highlight settings.
+    fn render(&self, board: &GameBoard) -> String {
+        let frame = if self.highlight {
+            board.render_highlight(self.empty_glyph)
+        } else {
+            board.render_with_empty(self.empty_glyph)
+        };
+
+        // The border never has O or X in it so every one is a stone, lower
+        // case if highlighted.
+        let frame = frame.chars().map(|ch| match ch {
+            'O'         => self.symbols[0],
+            'X'         => self.symbols[1],
+            'o'         => self.symbols[0].to_ascii_lowercase(),
+            'x'         => self.symbols[1].to_ascii_lowercase(),
+            _           => ch,
+        }).collect::<String>();
+
+        if self.labels {
+            board.label(&frame)
+        } else {
+            frame
+        }
+    }
+
+    /// Who plays each side, the board size, and whose turn it is, followed
+    /// by the board as render shows it. For debugging and logs.
+    pub fn render_state(&self, board: &GameBoard, current: &Player) -> String {
+        let kind = |player_type| match player_type {
+            'c' => "computer",
+            _   => "human",
+        };
+
+        format!("{}: {}\n{}: {}\nBoard: {}x{}\nTo play: {}\n{}",
+                self.player_name(&Player::O), kind(self.player1_type),
+                self.player_name(&Player::X), kind(self.player2_type),
+                board.get_height(), board.get_width(),
+                self.player_name(current),
+                self.render(board))
+    }
+
+    /// Prints the final board and who won, given the cell of a stone left
+    /// without liberties.
+    fn announce_win(&self, board: &GameBoard, h: usize, w: usize) -> Result<GameResult, NogoError> {
+        if !self.quiet {
+            print!("{}", self.render(board));
+        }
+        let result = GameResult { winner: Some(Nogo::winner_at(board, h, w)?), reason: EndReason::Capture };
+        println!("{}", self.result_line(&result));
+        self.print_timings();
+
+        Ok(result)
+    }
+
+    /// Line announcing how the game ended, in the --result-format format if
+    /// one was given.
+    fn result_line(&self, result: &GameResult) -> String {
+        if let Some(ref format) = self.result_format {
+            return result.format(format);
+        }
+
+        match (result.winner, result.reason) {
+            (Some(winner), _)           => format!("{} wins!", self.player_name(&winner)),
+            (None, EndReason::Draw)     => String::from("Draw!"),
+            (None, _)                   => String::from("Nobody wins."),
+        }
+    }
+
+    /// Takes back the last move, and the one before it too if that leaves a
+    /// computer to play, so the human who asked gets their turn back.
+    fn undo(&self,
+            board: &mut GameBoard,
+            computer1: &Option<Computer>,
+            computer2: &Option<Computer>,
+            current_player: &mut Player) {
+
+        let mut undone = false;
+
+        while let Some((h, w)) = board.undo() {
+            println!("Took back {} {}", h, w);
+            undone = true;
+
+            Nogo::change_player(current_player);
+
+            let is_computer = match *current_player {
+                Player::O => computer1.is_some(),
+                Player::X => computer2.is_some(),
+            };
+            if !is_computer {
+                break;
+            }
+        }
+
+        if !undone {
+            eprintln!("No moves to undo");
+        }
+
+        self.autosave(board, computer1.as_ref(), computer2.as_ref(), current_player);
+    }
+
+    /// Player who won given the cell of a stone left without liberties.
+    /// Returns NogoError::Internal if the cell is empty or off the board,
+    /// which means the win check is broken.
+    fn winner_at(board: &GameBoard, h: usize, w: usize) -> Result<Player, NogoError> {
+        match board.get(h, w) {
+            Some('O') => Ok(Player::X),
+            Some('X') => Ok(Player::O),
+             _        => Err(NogoError::Internal(format!("no stone at winning cell {} {}", h, w))),
+        }
+    }
+
+    /// Describes a position: every group and its liberties, groups in atari,
+    /// each player's mobility, and whether the game is over.
+    fn analysis(board: &GameBoard, next: &Player) -> Result<String, NogoError> {
+        let groups    = board.analyze_groups();
+        let mut lines = vec![format!("Groups: {}", groups.len())];
+
+        for group in &groups {
+            let (h, w) = group.cells[0];
+            lines.push(format!("{} group at {} {}: {}, {}{}",
+                               group.color, h, w,
+                               Nogo::counted(group.cells.len(), "stone", "stones"),
+                               Nogo::counted(group.liberties, "liberty", "liberties"),
+                               if group.liberties == 1 { " (atari)" } else { "" }));
+        }
+
+        lines.push(format!("Atari: {}", groups.iter().filter(|g| g.liberties == 1).count()));
+        lines.push(format!("Mobility: O {}, X {}",
+                           board.mobility(&Player::O)?, board.mobility(&Player::X)?));
+
+        lines.push(match board.try_check_win()? {
+            Some((h, w)) => format!("Status: player {} wins", Nogo::winner_at(board, h, w)?),
+            None => format!("Status: player {} to play", next),
+        });
+
+        Ok(lines.join("\n"))
+    }
+
+    /// n followed by one or many, whichever agrees with it.
+    fn counted(n: usize, one: &str, many: &str) -> String {
+        format!("{} {}", n, if n == 1 { one } else { many })
+    }
+
+    /// Saves to the file given with --save, if there was one.
+    fn autosave(&self,
+                board: &GameBoard,
+                c1: Option<&Computer>,
+                c2: Option<&Computer>,
+                player: &Player) {
+
+        if let Some(ref path) = self.autosave {
+            if self.save(path, board, c1, c2, player).is_err() {
+                eprintln!("Failed to save file");
+            }
+        }
+    }
+
+    /// Saves the game to a new file in the --repro directory named after the
+    /// current time. Returns the file's path.
+    fn write_repro(&self,
+                   board: &GameBoard,
+                   c1: Option<&Computer>,
+                   c2: Option<&Computer>,
+                   player: &Player) -> Result<String, Box<dyn Error>> {
+
+        let dir  = self.repro_dir.as_ref().ok_or("--repro not given")?;
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let path = Path::new(dir).join(format!("nogors-repro-{}.txt", secs));
+        let path = path.to_str().ok_or("repro path is not valid unicode")?;
+
+        self.save(path, board, c1, c2, player)?;
+
+        Ok(String::from(path))
+    }
+
+    /// Loads a saved game. Returns the board, both computers, and the player
+    /// who is next to play.
+    fn load(&mut self, filename: &str)
+        -> Result<(GameBoard, Option<Computer>, Option<Computer>, Player), NogoError>
+    {
+        let mut file     = File::open(filename).map_err(|e| NogoError::failed_to_open(filename, &e))?;
+        let mut contents = String::new();
+
+        file.read_to_string(&mut contents)?;
+        let contents = contents.split_at(contents.find('\n').ok_or(NogoError::corrupt(1, "no board after the header"))?);
+
+        let mut first_three = save_header(contents.0)?.split_whitespace().take(3);
+        let height = first_three.next().ok_or(NogoError::corrupt(1, "header missing height"))?;
+        let width = first_three.next().ok_or(NogoError::corrupt(1, "header missing width"))?;
+        let current_player = match first_three.next().ok_or(NogoError::corrupt(1, "header missing next-player field"))? {
+            "0" => Player::O,
+            "1" => Player::X,
+             _  => return Err(NogoError::corrupt(1, "next player must be 0 or 1")),
+        };
+
+        self.height = height.parse()?;
+        self.width  = width.parse()?;
+
+        // Overridden sequence starts follow the computers' progress, then
+        // the --ai-seed if there is one.
+        let starts = save_header(contents.0)?.split_whitespace().skip(9).collect::<Vec<&str>>();
+        if !starts.is_empty() {
+            let start = |fields: &[&str]| {
+                SequenceStart::parse(&fields.join(","))
+                    .ok_or(NogoError::corrupt(1, "sequence starts must be 6 numbers"))
+            };
+            if starts.len() != 6 && starts.len() != 7 {
+                return Err(NogoError::corrupt(1, "sequence starts must be 6 numbers"));
+            }
+            self.sequence_starts = [Some(start(&starts[..3])?), Some(start(&starts[3..6])?)];
+        }
+        if let Some(seed) = starts.get(6) {
+            self.ai_seed = seed.parse().map_err(|_| NogoError::corrupt(1, "AI seed must be a number"))?;
+        }
+
+        // Computers need the board size to resume their sequences.
+        let computer1 = Computer::load(self, contents.0, Player::O)?;
+        let computer2 = Computer::load(self, contents.0, Player::X)?;
+
+        // Height and width from the first 2 numbers in the file must match
+        // the board's. contents.1 starts with the header's newline so board
+        // errors have the file's line numbers.
+        let board = GameBoard::from_with_dims(contents.1, self.height, self.width)?;
+
+        if self.strict {
+            let mut last_player = current_player;
+            Nogo::change_player(&mut last_player);
+
+            if let Some((h, w)) = board.early_capture(&last_player)? {
+                return Err(NogoError::corrupt(h + 2, format!("group at {} {} was captured before the last move", h, w)));
+            }
+        }
+
+        Ok((board, computer1, computer2, current_player))
+    }
+
+    /// Loads a position from an SGF file. The player after the last move is
+    /// next, or if there are no moves the one with fewer stones. Computers
+    /// start their sequences from the beginning.
+    fn load_sgf(&mut self, filename: &str)
+        -> Result<(GameBoard, Option<Computer>, Option<Computer>, Player), NogoError>
+    {
+        let sgf   = fs::read_to_string(filename).map_err(|e| NogoError::failed_to_open(filename, &e))?;
+        let board = GameBoard::from_sgf(&sgf)?;
+
+        self.height = board.get_height();
+        self.width  = board.get_width();
+
+        let next = match board.get_history().last() {
+            Some(&(_, _, 'O')) => Player::X,
+            Some(_)            => Player::O,
+            None if board.stone_count(&Player::O) > board.stone_count(&Player::X) => Player::X,
+            None               => Player::O,
+        };
+
+        Ok((board, Computer::new(self, Player::O), Computer::new(self, Player::X), next))
+    }
+
+    /// Save current game state to file. The file is either fully replaced
+    /// or left as it was.
+    fn save(&self,
+            filename: &str,
+            board: &GameBoard, 
+            c1: Option<&Computer>, 
+            c2: Option<&Computer>, 
+            player: &Player) -> Result<(), Box<dyn Error>> {
+        write_atomic(filename, |temp| self.save_unchecked(temp, board, c1, c2, player))
+    }
+
+    /// Writes the save straight to filename. A failure part way through
+    /// leaves it half written.
+    fn save_unchecked(&self,
+                      filename: &str,
+                      board: &GameBoard,
+                      c1: Option<&Computer>,
+                      c2: Option<&Computer>,
+                      player: &Player) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(filename).map_err(|e| NogoError::failed_to_open(filename, &e))?;
+
+        // 0 means O is next to play. 1 means X is next to play.
+        let next_to_play = match *player {
+            Player::O => 0,
+            Player::X => 1,
+        };
+
+        let c1_row;
+        let c1_column;
+        let c1_counter;
+        match c1 {
+            Some(c) => {
+                c1_row      = c.get_row();
+                c1_column   = c.get_column();
+                c1_counter  = c.get_counter();
+            },
+            None => {
+                c1_row      = 0;
+                c1_column   = 0;
+                c1_counter  = 0;
+            },
+        }
+
+        let c2_row;
+        let c2_column;
+        let c2_counter;
+        match c2 {
+            Some(c) => {
+                c2_row      = c.get_row();
+                c2_column   = c.get_column();
+                c2_counter  = c.get_counter();
+            },
+            None => {
+                c2_row      = 0;
+                c2_column   = 0;
+                c2_counter  = 0;
+            },
+        }
+
+        write!(file, "{} {} {} {} {} {} {} {} {} {}",
+               SAVE_FORMAT_TAG,
+               self.height,   self.width,     next_to_play,
+               c1_row,        c1_column,      c1_counter,
+               c2_row,        c2_column,      c2_counter)?;
+
+        // Sequence starts are only written when one was overridden or an
+        // --ai-seed follows them so other saves stay the same.
+        if self.sequence_starts.iter().any(Option::is_some) || self.ai_seed != 0 {
+            for &player in &[Player::O, Player::X] {
+                let start = self.get_sequence_start(player);
+                write!(file, " {} {} {}", start.row, start.column, start.mult_factor)?;
+            }
+        }
+        if self.ai_seed != 0 {
+            write!(file, " {}", self.ai_seed)?;
+        }
+        writeln!(file)?;
+
+        board.save(&mut file)?;
+
+        Ok(())
+    }
+
+    /// Saves the same game state as save but as a JSON document. Also
+    /// replaces the file all at once.
+    pub fn save_json(&self,
+                     filename: &str,
+                     board: &GameBoard,
+                     c1: Option<&Computer>,
+                     c2: Option<&Computer>,
+                     player: &Player) -> Result<(), NogoError> {
+        let computer = |c: Option<&Computer>, player| match c {
+            Some(c) => {
+                let start = self.get_sequence_start(player);
+                Value::Object(vec![
+                    (String::from("row"),       Value::Number(c.get_row())),
+                    (String::from("column"),    Value::Number(c.get_column())),
+                    (String::from("counter"),   Value::Number(c.get_counter())),
+                    (String::from("start"),     Value::Array(vec![
+                        Value::Number(start.row),
+                        Value::Number(start.column),
+                        Value::Number(start.mult_factor),
+                    ])),
+                ])
+            },
+            None    => Value::Null,
+        };
+
+        let rows = (0..board.get_height()).map(|h| {
+            let row = (0..board.get_width()).filter_map(|w| board.get(h, w)).collect();
+            Value::Str(row)
+        }).collect();
+
+        let mut fields = vec![
+            (String::from("format"),    Value::Str(String::from(SAVE_FORMAT_TAG))),
+            (String::from("height"),    Value::Number(board.get_height())),
+            (String::from("width"),     Value::Number(board.get_width())),
+            (String::from("next"),      Value::Str(player.to_string())),
+            (String::from("computer1"), computer(c1, Player::O)),
+            (String::from("computer2"), computer(c2, Player::X)),
+            (String::from("board"),     Value::Array(rows)),
+        ];
+        if self.ai_seed != 0 {
+            fields.push((String::from("ai_seed"), Value::Number(self.ai_seed)));
+        }
+        let document = Value::Object(fields);
+
+        write_atomic(filename, |temp| {
+            let mut file = File::create(temp).map_err(|e| NogoError::failed_to_open(temp, &e))?;
+            writeln!(file, "{}", document)?;
+            Ok(())
+        })
+    }
+
+    /// Loads a game written by save_json.
+    pub fn load_json(&mut self, filename: &str)
+        -> Result<(GameBoard, Option<Computer>, Option<Computer>, Player), NogoError>
+    {
+        let mut contents = String::new();
+        File::open(filename).map_err(|e| NogoError::failed_to_open(filename, &e))?
+                            .read_to_string(&mut contents)?;
+
+        let document = json::parse(&contents).ok_or(NogoError::corrupt(0, "not valid JSON"))?;
+        let number   = |key| {
+            document.get(key).and_then(Value::as_number)
+                    .ok_or_else(|| NogoError::corrupt(0, format!("missing number \"{}\"", key)))
+        };
+
+        if document.get("format").and_then(Value::as_str) != Some(SAVE_FORMAT_TAG) {
+            return Err(NogoError::corrupt(0, format!("format isn't \"{}\"", SAVE_FORMAT_TAG)));
+        }
+
+        self.height = number("height")?;
+        self.width  = number("width")?;
+
+        let current_player = match document.get("next").and_then(Value::as_str) {
+            Some("O") => Player::O,
+            Some("X") => Player::X,
+            _         => return Err(NogoError::corrupt(0, "next player must be \"O\" or \"X\"")),
+        };
+
+        // Older saves have no "start", which means the usual one.
+        for (index, &(key, player)) in [("computer1", Player::O), ("computer2", Player::X)].iter().enumerate() {
+            let start = match document.get(key).and_then(|c| c.get("start")) {
+                Some(start) => start,
+                None        => continue,
+            };
+            let fields = start.as_array().unwrap_or(&[]).iter().map(Value::to_string).collect::<Vec<String>>();
+            let start  = SequenceStart::parse(&fields.join(","))
+                             .ok_or_else(|| NogoError::corrupt(0, format!("\"start\" in \"{}\" must be 3 numbers", key)))?;
+
+            self.sequence_starts[index] = Some(start).filter(|&s| s != SequenceStart::default_for(player));
+        }
+
+        // Older saves have no "ai_seed", which means the usual order.
+        if document.get("ai_seed").is_some() {
+            self.ai_seed = number("ai_seed")?;
+        }
+
+        let computer = |key: &str, player: Player| -> Result<Option<Computer>, NogoError> {
+            let saved = document.get(key).ok_or_else(|| NogoError::corrupt(0, format!("missing \"{}\"", key)))?;
+            let field = |field| {
+                saved.get(field).and_then(Value::as_number)
+                     .ok_or_else(|| NogoError::corrupt(0, format!("missing number \"{}\" in \"{}\"", field, key)))
+            };
+
+            Ok(match *saved {
+                Value::Null => Computer::new(self, player),
+                _           => Computer::resume(self, player, field("row")?, field("column")?, field("counter")?),
+            })
+        };
+        let computer1 = computer("computer1", Player::O)?;
+        let computer2 = computer("computer2", Player::X)?;
+
+        let rows = document.get("board").and_then(Value::as_array)
+                           .ok_or(NogoError::corrupt(0, "missing \"board\" rows"))?;
+        let rows = rows.iter()
+                       .map(|row| row.as_str().ok_or(NogoError::corrupt(0, "board rows must be strings")))
+                       .collect::<Result<Vec<&str>, NogoError>>()?;
+
+        let board = GameBoard::from_with_dims(&rows.join("\n"), self.height, self.width)?;
+
+        Ok((board, computer1, computer2, current_player))
+    }
+
+    /// Breakdown of where time was spent. None unless started with --timings.
+    fn timing_summary(&self) -> Option<String> {
+        self.timings.as_ref().map(|t| t.to_string())
+    }
+
+    /// Prints the timing_summary, if there is one, after a game ends.
+    fn print_timings(&self) {
+        if let Some(summary) = self.timing_summary() {
+            println!("{}", summary);
+        }
+    }
+
+    pub fn get_p1type(&self) -> char {
+        self.player1_type
+    }
+
+    pub fn get_p2type(&self) -> char {
+        self.player2_type
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    /// Makes the computer playing player use strategy in the next run. Does
+    /// nothing if player is human.
+    pub fn set_strategy(&mut self, player: Player, strategy: Box<dyn Strategy>) {
+        match player {
+            Player::O => self.strategy1 = Some(strategy),
+            Player::X => self.strategy2 = Some(strategy),
+        }
+    }
+
+    pub fn get_strength(&self) -> u8 {
+        self.strength
+    }
+
+    pub fn get_difficulty(&self) -> Option<Difficulty> {
+        self.difficulty
+    }
+
+    pub fn get_ai_seed(&self) -> usize {
+        self.ai_seed
+    }
+
+    /// Start of player's computer move sequence, overridden or not.
+    pub fn get_sequence_start(&self, player: Player) -> SequenceStart {
+        let index = match player {
+            Player::O => 0,
+            Player::X => 1,
+        };
+
+        self.sequence_starts[index].unwrap_or_else(|| SequenceStart::default_for(player))
+    }
+
+    /// Gets move from computer or player. Saves current game to specified
+    /// file from user.
+    fn get_move<R: BufRead>(&mut self,
+                            input: &mut R,
+                            board: &GameBoard,
+                            c1: Option<&mut Computer>,
+                            c2: Option<&mut Computer>,
+                            player: &Player)
+        -> Result<Move, NogoError>
+    {
+        let computer = match *player {
+            Player::O => c1,
+            Player::X => c2,
+        };
+
+        if let Some(ref c) = computer {
+            if self.explain_ai {
+                println!("AI {}: {}", player, c.explain(board, player)?);
+            }
+        }
+
+        self.prompt(player);
+
+        if let Some(c) = computer {
+            let next = match c.choose_move(board, player)? {
+                Some((h, w)) => Move::Place(h, w),
+                None         => Move::Pass,
+            };
+
+            if !self.quiet {
+                match next {
+                    Move::Place(h, w) => println!("{} {}", h, w),
+                    _                 => println!("pass"),
+                }
+            }
+            return Ok(next);
+        }
+
+        loop {
+            let line = Nogo::get_player_move(input);
+
+            let timed_out = match line {
+                Err(ref e) => e.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::TimedOut),
+                _          => false,
+            };
+            if timed_out {
+                if !self.quiet {
+                    println!();
+                }
+                println!("{} ran out of time", self.player_name(player));
+
+                return Ok(match self.timeout_rule {
+                    TimeoutRule::Pass => Move::Pass,
+                    TimeoutRule::Lose => Move::OutOfTime,
+                });
+            }
+
+            self.lines_read += 1;
+
+            let tokens = match line {
+                Ok(Some(t)) => t,
+                Ok(None)    => {
+                    if !self.quiet {
+                        println!();
+                    }
+                    return Ok(Move::Quit);
+                },
+                Err(e) => {
+                    self.reject(player, &e.to_string())?;
+                    continue;
+                },
+            };
+
+            if tokens[0] == "q" || tokens[0] == "quit" {
+                return Ok(Move::Quit);
+            }
+
+            if tokens[0] == "?" || tokens[0] == "help" {
+                println!("{}", self.help_text());
+                self.prompt(player);
+                continue;
+            }
+
+            if tokens[0] == "log" {
+                let log = Nogo::move_log(board, self.log_size);
+                if !log.is_empty() {
+                    println!("{}", log);
+                }
+                self.prompt(player);
+                continue;
+            }
+
+            if tokens[0] == "undo" {
+                return Ok(Move::Undo);
+            }
+
+            if tokens[0] == "pass" {
+                return Ok(Move::Pass);
+            }
+
+            if tokens[0] == "order" {
+                self.toggle_coord_order();
+                println!("Enter moves as: {}", self.coord_order);
+                self.prompt(player);
+                continue;
+            }
+
+            if tokens.len() < 2 {
+                self.reject(player, "please enter 2 numbers")?;
+                continue;
+            }
+
+            if tokens[0] == "w" {
+                println!("Saving to {}", tokens[1]);
+                self.filename = tokens[1].clone();
+                self.is_save = true;
+                return Ok(Move::Place(0, 0));  // Leave function to go save.
+            }
+
+            if tokens.len() > 2 {
+                self.reject(player, "too many values")?;
+                continue;
+            }
+
+            let first: usize = match tokens[0].parse() {
+                Ok(u)  => u,
+                Err(e) => {
+                    self.reject(player, &e.to_string())?;
+                    continue;
+                },
+            };
+
+            let second: usize = match tokens[1].parse() {
+                Ok(u)  => u,
+                Err(e) => {
+                    self.reject(player, &e.to_string())?;
+                    continue;
+                },
+            };
+
+            let (h, w) = match self.coord_order {
+                CoordOrder::RowFirst    => (first, second),
+                CoordOrder::ColumnFirst => (second, first),
+            };
+
+            // Ask again straight away rather than after trying the move.
+            if let Err(e) = board.can_place(h, w) {
+                self.reject(player, &e.to_string())?;
+                continue;
+            }
+
+            return Ok(Move::Place(h, w));
+        }
+    }
+
+    /// Gets player move from input. Returns the whitespace separated words
+    /// of the line, there is always at least one. Anything after a '#' is a
+    /// comment and ignored. Returns None if there is no more input.
+    fn get_player_move<R: BufRead>(input: &mut R)
+        -> Result<Option<Vec<String>>, Box<dyn Error>>
+    {
+        let mut buffer = String::new();
+
+        if input.read_line(&mut buffer)? == 0 {
+            return Ok(None);
+        }
+
+        let line = buffer.split('#').next().unwrap_or("");
+        let tokens: Vec<String> = line.split_whitespace()
+                                        .map(String::from)
+                                        .collect();
+
+        if tokens.is_empty() {
+            return Err(From::from("please enter 2 numbers"));
+        }
+
+        Ok(Some(tokens))
+    }
+
+    /// Reports bad input from a human player. In batch mode this is an error,
+    /// otherwise the player is asked again.
+    fn reject(&self, player: &Player, message: &str) -> Result<(), NogoError> {
+        if self.batch {
+            return Err(NogoError::BadInput(self.lines_read, String::from(message)));
+        }
+
+        eprintln!("Error: {}", message);
+        self.prompt(player);
+
+        Ok(())
+    }
+
+    /// Lists the last count moves placed on the board, oldest first. Each
+    /// line is "move number: player row column".
+    fn move_log(board: &GameBoard, count: usize) -> String {
+        let history = board.get_history();
+        let start   = history.len().saturating_sub(count);
+
+        history.iter()
+               .enumerate()
+               .skip(start)
+               .map(|(i, &(h, w, player))| format!("{}: {} {} {}", i + 1, player, h, w))
+               .collect::<Vec<String>>()
+               .join("\n")
+    }
+
+    /// Everything that can be entered at the move prompt, one per line.
+    fn help_text(&self) -> String {
+        format!("{:<12}place a stone\n\
+                 {:<12}save the game to file\n\
+                 {:<12}skip your turn\n\
+                 {:<12}take back your last move\n\
+                 {:<12}show recent moves\n\
+                 {:<12}swap row and column order\n\
+                 {:<12}leave the game\n\
+                 {:<12}show this list",
+                self.coord_order.to_string(), "w file", "pass", "undo", "log", "order", "q, quit", "?, help")
+    }
+
+    /// Prompt asking player for the next move.
+    fn prompt_text(&self, player: &Player) -> String {
+        format!("Move {}, {}> ", self.move_number, self.player_name(player))
+    }
+
+    /// How player is shown in prompts and results, "Player O" unless a name
+    /// or symbol was given, then like "Alice (O)".
+    fn player_name(&self, player: &Player) -> String {
+        let index = match *player {
+            Player::O => 0,
+            Player::X => 1,
+        };
+
+        match self.names[index] {
+            Some(ref name) => format!("{} ({})", name, self.symbols[index]),
+            None           => format!("Player {}", self.symbols[index]),
+        }
+    }
+
+    /// Prints the prompt for the given player, unless quiet.
+    fn prompt(&self, player: &Player) {
+        if self.quiet {
+            return;
+        }
+
+        print!("{}", self.prompt_text(player));
+        io::stdout().flush().unwrap();
+    }
+
+    /// Switches between entering moves row first and column first.
+    fn toggle_coord_order(&mut self) {
+        self.coord_order = match self.coord_order {
+            CoordOrder::RowFirst    => CoordOrder::ColumnFirst,
+            CoordOrder::ColumnFirst => CoordOrder::RowFirst,
+        };
+    }
+
+    /// Change current player to next player.
+    fn change_player(current_player: &mut Player) {
+        *current_player = match *current_player {
+            Player::O => Player::X,
+            Player::X => Player::O,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+    use computer::DeterministicStrategy;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        let mut args = vec![String::from("nogors")];
+        args.extend(list.iter().map(|a| String::from(*a)));
+        args
+    }
+
+    /// A file in the temp directory that is removed when dropped, even if the
+    /// test fails. Names include the process id so test runs don't collide.
+    struct TempFile(::std::path::PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> TempFile {
+            let name = format!("nogors_test_{}_{}", ::std::process::id(), name);
+            TempFile(::std::env::temp_dir().join(name))
+        }
+
+        fn path(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = ::std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_change_player() {
+        let mut current_player = Player::O;
+
+        assert!(matches!(current_player, Player::O));
+
+        Nogo::change_player(&mut current_player);
+
+        assert!(matches!(current_player, Player::X));
+    }
+
+    #[test]
+    fn test_result_sgf() {
+        let capture = GameResult { winner: Some(Player::O), reason: EndReason::Capture };
+        let draw    = GameResult { winner: None,            reason: EndReason::Draw };
+        let abandon = GameResult { winner: None,            reason: EndReason::Abandonment };
+
+        assert_eq!("B+",   capture.to_sgf());
+        assert_eq!("0",    draw.to_sgf());
+        assert_eq!("Void", abandon.to_sgf());
+        assert_eq!("B+T",  GameResult { winner: Some(Player::O), reason: EndReason::Timeout }.to_sgf());
+    }
+
+    #[test]
+    fn test_render_state() {
+        let nogo  = Nogo::from_args(args(&["c", "h", "4", "5"])).unwrap();
+        let board = GameBoard::new(4, 5).unwrap();
+
+        assert_eq!("Player O: computer\n\
+                    Player X: human\n\
+                    Board: 4x5\n\
+                    To play: Player X\n\
+                    /-----\\\n|.....|\n|.....|\n|.....|\n|.....|\n\\-----/\n",
+                   nogo.render_state(&board, &Player::X));
+
+        let nogo = Nogo::from_args(args(&["--x-name", "Bob", "h", "c", "4", "5"])).unwrap();
+        let state = nogo.render_state(&board, &Player::O);
+        assert!(state.starts_with("Player O: human\nBob (X): computer\nBoard: 4x5\nTo play: Player O\n"));
+    }
+
+    #[test]
+    fn test_names_and_symbols() {
+        let file = TempFile::new("names.txt");
+        let path = file.path();
+
+        let o_win = GameResult { winner: Some(Player::O), reason: EndReason::Capture };
+        let x_win = GameResult { winner: Some(Player::X), reason: EndReason::Capture };
+
+        let nogo = Nogo::from_args(args(&["--o-name", "Alice", "--x-symbol", "#",
+                                          "h", "h", "4", "4"])).unwrap();
+        assert_eq!("Alice (O) wins!", nogo.result_line(&o_win));
+        assert_eq!("Player # wins!", nogo.result_line(&x_win));
+        assert_eq!("Move 1, Alice (O)> ", nogo.prompt_text(&Player::O));
+
+        let mut board = GameBoard::new(4, 4).unwrap();
+        board.insert_move(0, 0, &Player::X).unwrap();
+        board.insert_move(1, 1, &Player::O).unwrap();
+        assert_eq!("/----\\\n|#...|\n|.O..|\n|....|\n|....|\n\\----/\n", nogo.render(&board));
+
+        // Saves keep O and X.
+        nogo.save(path, &board, None, None, &Player::X).unwrap();
+        let saved = ::std::fs::read_to_string(path).unwrap();
+        assert!(saved.ends_with("X...\n.O..\n....\n....\n"));
+        ::std::fs::remove_file(path).unwrap();
+
+        for bad in &[&["--o-symbol", "X"][..], &["--x-symbol", "."], &["--o-symbol", "|"],
+                     &["--o-symbol", "ab"], &["--empty", "+", "--x-symbol", "+"]] {
+            let list = bad.iter().chain(&["h", "h", "4", "4"]).cloned().collect::<Vec<&str>>();
+            assert!(Config::parse(args(&list).into_iter()).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_failed_to_open() {
+        let path = ::std::env::temp_dir().join("nogors_test_missing").join("game.txt");
+        let path = path.to_str().unwrap();
+
+        let mut nogo = Nogo::from_args(args(&["h", "h", path])).unwrap();
+        let e = nogo.run_with(&mut Cursor::new("")).unwrap_err();
+        assert_eq!(format!("Unable to open file: {} (not found)", path), e.to_string());
+
+        let e = nogo.load_json(path).unwrap_err();
+        assert_eq!(format!("Unable to open file: {} (not found)", path), e.to_string());
+
+        // Saving into a missing directory names the file being written.
+        let board = GameBoard::new(4, 4).unwrap();
+        let e = nogo.save_json(path, &board, None, None, &Player::O).unwrap_err();
+        assert_eq!(format!("Unable to open file: {}.tmp (not found)", path), e.to_string());
+
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert_eq!("Unable to open file: a.txt (permission denied)",
+                   NogoError::failed_to_open("a.txt", &denied).to_string());
+        let other = io::Error::from(io::ErrorKind::Other);
+        assert_eq!("Unable to open file: a.txt", NogoError::failed_to_open("a.txt", &other).to_string());
+    }
+
+    #[test]
+    fn test_failed_to_open_read_only() {
+        let dir  = ::std::env::temp_dir().join("nogors_test_read_only");
+        let path = dir.join("game.txt");
+        let path = path.to_str().unwrap();
+        let _    = fs::create_dir(&dir);
+
+        let mut permissions = fs::metadata(&dir).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&dir, permissions).unwrap();
+
+        let nogo   = Nogo::from_args(args(&["h", "h", "4", "4"])).unwrap();
+        let board  = GameBoard::new(4, 4).unwrap();
+        let result = nogo.save_json(path, &board, None, None, &Player::O);
+
+        #[allow(clippy::permissions_set_readonly_false)]
+        {
+            let mut permissions = fs::metadata(&dir).unwrap().permissions();
+            permissions.set_readonly(false);
+            fs::set_permissions(&dir, permissions).unwrap();
+        }
+        let _ = fs::remove_file(path);
+        fs::remove_dir(&dir).unwrap();
+
+        // Administrators can write anywhere so there is nothing to check.
+        if let Err(e) = result {
+            assert_eq!(format!("Unable to open file: {}.tmp (permission denied)", path), e.to_string());
+        }
+    }
+
+    #[test]
+    fn test_sgf_import() {
+        let file = TempFile::new("import.sgf");
+        let path = file.path();
+
+        // X's corner stone has one liberty left and O is next.
+        ::std::fs::write(path, "(;SZ[4]AB[ba]AW[aa])").unwrap();
+        let mut nogo = Nogo::from_args(args(&["h", "h", path])).unwrap();
+        assert_eq!(GameOutcome::Winner(Player::O), nogo.run_with(&mut Cursor::new("1 0\n")).unwrap());
+        assert_eq!((4, 4), (nogo.get_height(), nogo.get_width()));
+
+        ::std::fs::write(path, "(;SZ[4];B[bb])").unwrap();
+        let mut nogo = Nogo::from_args(args(&["h", "h", path])).unwrap();
+        let (board, _, _, player) = nogo.load_sgf(path).unwrap();
+        assert_eq!(Some('O'), board.get(1, 1));
+        assert_eq!(Player::X, player);
+    }
+
+    #[test]
+    fn test_sgf_export() {
+        let file = TempFile::new("export.sgf");
+        let path = file.path();
+
+        let mut nogo = Nogo::from_args(args(&["--sgf", path, "h", "h", "4", "4"])).unwrap();
+        nogo.run_with(&mut Cursor::new("0 1\n0 0\n1 0\n")).unwrap();
+        let sgf = ::std::fs::read_to_string(path).unwrap();
+        assert!(sgf.starts_with("(;FF[4]GM[1]SZ[4]RE[B+]"), "{}", sgf);
+        assert!(sgf.contains(";B[ba];W[aa];B[ab]"), "{}", sgf);
+
+        // A game left part way through is written too.
+        let mut nogo = Nogo::from_args(args(&["--sgf", path, "h", "h", "5", "4"])).unwrap();
+        nogo.run_with(&mut Cursor::new("2 3\nquit\n")).unwrap();
+        assert_eq!("(;FF[4]GM[1]SZ[4:5]RE[Void];B[dc])", ::std::fs::read_to_string(path).unwrap().trim_end());
+        ::std::fs::remove_file(path).unwrap();
+
+        // Boards SGF can't describe are refused up front when their size is
+        // given, and not written when loaded.
+        assert!(Nogo::from_args(args(&["--sgf", path, "h", "h", "52", "52"])).is_ok());
+        for size in &[&["53"][..], &["4", "53"], &["60", "4"]] {
+            let mut list = vec!["--sgf", path, "h", "h"];
+            list.extend(size.iter());
+            match Nogo::from_args(args(&list)) {
+                Err(NogoError::InvalidDimension) => {},
+                r => panic!("{:?} gave {:?}", size, r.map(|_| ())),
+            }
+        }
+
+        let big  = TempFile::new("export_big.txt");
+        let save = big.path();
+        ::std::fs::write(save, format!("nogo v1 53 53 0 0 0 0 0 0 0\n{}", format!("{}\n", ".".repeat(53)).repeat(53))).unwrap();
+        let mut nogo = Nogo::from_args(args(&["--quiet", "--sgf", path, "h", "h", save])).unwrap();
+        assert_eq!(GameOutcome::Aborted, nogo.run_with(&mut Cursor::new("quit\n")).unwrap());
+        assert!(!Path::new(path).exists());
+    }
+
+    #[test]
+    fn test_result_format() {
+        let win  = GameResult { winner: Some(Player::X), reason: EndReason::Capture };
+        let draw = GameResult { winner: None,            reason: EndReason::Draw };
+
+        let nogo = Nogo::from_args(args(&["h", "h", "7", "7"])).unwrap();
+        assert_eq!("Player X wins!", nogo.result_line(&win));
+        assert_eq!("Draw!",          nogo.result_line(&draw));
+
+        let nogo = Nogo::from_args(args(&["--result-format", "RESULT winner={winner} loser={loser} reason={reason}",
+                                    "h", "h", "7", "7"])).unwrap();
+        assert_eq!("RESULT winner=X loser=O reason=capture", nogo.result_line(&win));
+        assert_eq!("RESULT winner=none loser=none reason=draw", nogo.result_line(&draw));
+    }
+
+    #[test]
+    fn test_timings_flag() {
+        let nogo = Nogo::from_args(args(&["h", "h", "7", "7"])).unwrap();
+        assert!(nogo.timing_summary().is_none());
+
+        let nogo    = Nogo::from_args(args(&["--timings", "h", "h", "7", "7"])).unwrap();
+        let summary = nogo.timing_summary().unwrap();
+        assert!(summary.starts_with("Timings:"));
+        assert!(summary.contains("computer:"));
+        assert_eq!(nogo.get_height(), 7);
+
+        assert!(Nogo::from_args(args(&["--bogus", "h", "h", "7", "7"])).is_err());
+    }
+
+    #[test]
+    fn test_autosave() {
+        let file = TempFile::new("autosave.txt");
+        let path = file.path();
+
+        let mut nogo  = Nogo::from_args(args(&["--save", path, "c", "h", "7", "7"])).unwrap();
+        let mut board = GameBoard::new(7, 7).unwrap();
+        let mut c1    = Computer::new(&nogo, Player::O);
+
+        let (h, w) = c1.as_mut().unwrap().choose_move(&board, &Player::O).unwrap().unwrap();
+        board.insert_move(h, w, &Player::O).unwrap();
+        nogo.autosave(&board, c1.as_ref(), None, &Player::X);
+
+        let (loaded, l1, l2, player) = nogo.load(path).unwrap();
+        for h in 0..7 {
+            for w in 0..7 {
+                assert_eq!(board.get(h, w), loaded.get(h, w));
+            }
+        }
+        let (c1, l1) = (c1.unwrap(), l1.unwrap());
+        assert_eq!(c1.get_counter(), l1.get_counter());
+        assert_eq!(c1.get_row(),     l1.get_row());
+        assert!(l2.is_none());
+        assert_eq!(player, Player::X);
+    }
+
+    #[test]
+    fn test_save_atomic() {
+        let file = TempFile::new("save_atomic.txt");
+        let path = file.path();
+        let temp = format!("{}.tmp", path);
+
+        let nogo  = Nogo::from_args(args(&["h", "h", "4", "4"])).unwrap();
+        let mut board = GameBoard::new(4, 4).unwrap();
+        board.insert_move(1, 1, &Player::O).unwrap();
+        nogo.save(path, &board, None, None, &Player::X).unwrap();
+        assert!(!Path::new(&temp).exists());
+        let saved = ::std::fs::read_to_string(path).unwrap();
+
+        // A directory in the way of the temp file makes writing it fail.
+        ::std::fs::create_dir(&temp).unwrap();
+        board.insert_move(2, 2, &Player::X).unwrap();
+        assert!(nogo.save(path, &board, None, None, &Player::O).is_err());
+        assert!(nogo.save_json(path, &board, None, None, &Player::O).is_err());
+        assert_eq!(saved, ::std::fs::read_to_string(path).unwrap());
+
+        ::std::fs::remove_dir(&temp).unwrap();
+        ::std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_replay() {
+        let replay = |moves: &str| Nogo::from_args(args(&["h", "h", "4", "4"])).unwrap().replay(Cursor::new(moves));
+
+        // X's stone in the corner has no liberties left.
+        let game = "0 1\n2 2\n1 0 # O closes in\n\n0 0\n3 3\n";
+        assert_eq!(GameOutcome::Winner(Player::O), replay(game).unwrap());
+
+        assert_eq!(GameOutcome::Draw, replay("1 1\npass\npass\n").unwrap());
+        assert_eq!(GameOutcome::Aborted, replay("1 1\npass\n2 2\n").unwrap());
+
+        for &(moves, line) in &[("1 1\n1 1\n", 2), ("1 1\n\nx 2\n", 3), ("1\n", 1), ("9 0\n", 1)] {
+            match replay(moves) {
+                Err(NogoError::BadInput(l, _)) => assert_eq!(line, l, "{:?}", moves),
+                r                              => panic!("{:?} gave {:?}", moves, r),
+            }
+        }
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        assert!(Nogo::get_player_move(&mut Cursor::new("")).unwrap().is_none());
+        assert!(Nogo::get_player_move(&mut Cursor::new("\n")).is_err());
+
+        // Running out of input ends the game, even after lines that had to
+        // be asked again.
+        for input in &["", "\n\n  \n", "1 1\n# comment\n"] {
+            let mut nogo = Nogo::from_args(args(&["h", "h", "4", "4"])).unwrap();
+            assert_eq!(GameOutcome::Aborted, nogo.run_with(&mut Cursor::new(*input)).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_save_on_exit() {
+        let file = TempFile::new("save_on_exit.txt");
+        let path = file.path();
+
+        let mut nogo = Nogo::from_args(args(&["--save-on-exit", "--save", path,
+                                        "h", "h", "5", "5"])).unwrap();
+        // Only save when the game is abandoned.
+        nogo.autosave = None;
+
+        for input in &["1 1\n2 2\n1 2\nquit\n", "1 1\n2 2\n1 2\n"] {
+            nogo.run_with(&mut Cursor::new(*input)).unwrap();
+
+            let (board, _, _, player) = nogo.load(path).unwrap();
+            assert_eq!(Some('O'), board.get(1, 1));
+            assert_eq!(Some('X'), board.get(2, 2));
+            assert_eq!(Some('O'), board.get(1, 2));
+            assert_eq!(Player::X, player);
+
+            ::std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_repro() {
+        let mut nogo = Nogo::from_args(args(&["--repro", "h", "h", "4", "4"])).unwrap();
+        assert_eq!(Some(String::from(".")), nogo.repro_dir);
+        nogo.repro_dir = Some(String::from(::std::env::temp_dir().to_str().unwrap()));
+
+        // Any group of two stones is too big to check.
+        let mut board  = GameBoard::new(4, 4).unwrap();
+        let mut player = Player::O;
+        board.set_flood_limit(1);
+
+        let result = nogo.play(&mut Cursor::new("0 0\n3 3\n0 1\n"),
+                               &mut board, &mut None, &mut None, &mut player);
+        assert!(matches!(result, Err(NogoError::Internal(_))));
+
+        let path  = nogo.write_repro(&board, None, None, &player).unwrap();
+        let _file = TempFile(path.clone().into());
+        assert!(path.contains("nogors-repro-"));
+
+        let (loaded, _, _, next) = nogo.load(&path).unwrap();
+        assert_eq!(Some('O'), loaded.get(0, 1));
+        assert_eq!(Some('X'), loaded.get(3, 3));
+        assert_eq!(Player::O, next);
+    }
+
+    #[test]
+    fn test_width_warning() {
+        assert_eq!(None, Nogo::width_warning(7, 80));
+        assert_eq!(None, Nogo::width_warning(78, 80));
+        assert_eq!(Some(String::from("Warning: the board is 81 columns wide with its border but \
+                                      the terminal is 80, so rows will wrap. Try a smaller \
+                                      board or a wider terminal.")),
+                   Nogo::width_warning(79, 80));
+        assert!(Nogo::width_warning(1000, 200).is_some());
+    }
+
+    #[test]
+    fn test_run_outcome() {
+        // Two computers play to the end without reading any input.
+        let mut nogo = Nogo::from_args(args(&["c", "c", "4", "4"])).unwrap();
+        assert_eq!(GameOutcome::Winner(Player::O), nogo.run_with(&mut Cursor::new("")).unwrap());
+
+        let mut nogo = Nogo::from_args(args(&["h", "h", "4", "4"])).unwrap();
+        assert_eq!(GameOutcome::Draw, nogo.run_with(&mut Cursor::new("pass\npass\n")).unwrap());
+
+        let mut nogo = Nogo::from_args(args(&["h", "h", "4", "4"])).unwrap();
+        assert_eq!(GameOutcome::Aborted, nogo.run_with(&mut Cursor::new("quit\n")).unwrap());
+
+        // "q" works at X's prompt too and nothing after it is read.
+        let mut nogo  = Nogo::from_args(args(&["--batch", "h", "h", "4", "4"])).unwrap();
+        let mut input = Cursor::new("1 1\nq\nnot a move\n");
+        assert_eq!(GameOutcome::Aborted, nogo.run_with(&mut input).unwrap());
+        assert_eq!(2, nogo.lines_read);
+    }
+
+    #[test]
+    fn test_rematch() {
+        // Two computer games, then "n" ends the session.
+        let mut nogo  = Nogo::from_args(args(&["--quiet", "--rematch", "c", "c", "4", "4"])).unwrap();
+        let mut input = Cursor::new("y\nn\nnot read\n");
+        assert_eq!(GameOutcome::Winner(Player::O), nogo.run_with(&mut input).unwrap());
+        assert_eq!(Scoreboard { o_wins: 2, x_wins: 0, draws: 0 }, nogo.get_scoreboard());
+        assert_eq!(2, nogo.lines_read);
+
+        // Strategies from set_strategy play every game, carrying on from one
+        // to the next.
+        #[derive(Debug)]
+        struct Passer(::std::rc::Rc<::std::cell::Cell<usize>>);
+
+        impl Strategy for Passer {
+            fn next_move(&mut self, _board: &GameBoard, _player: &Player) -> Result<Option<(usize, usize)>, NogoError> {
+                self.0.set(self.0.get() + 1);
+                Ok(None)
+            }
+        }
+
+        let calls    = ::std::rc::Rc::new(::std::cell::Cell::new(0));
+        let mut nogo = Nogo::from_args(args(&["--quiet", "--rematch", "c", "c", "4", "4"])).unwrap();
+        nogo.set_strategy(Player::X, Box::new(Passer(calls.clone())));
+        nogo.set_strategy(Player::O, Box::new(Passer(calls.clone())));
+        assert_eq!(GameOutcome::Draw, nogo.run_with(&mut Cursor::new("y\ny\nn\n")).unwrap());
+        assert_eq!(Scoreboard { o_wins: 0, x_wins: 0, draws: 3 }, nogo.get_scoreboard());
+        assert_eq!(6, calls.get());
+
+        // O wins the first game, X the second, then a draw. The end of input
+        // ends the session.
+        let mut nogo  = Nogo::from_args(args(&["--quiet", "--rematch", "--o-name", "Alice", "h", "h", "4", "4"])).unwrap();
+        let mut input = Cursor::new("0 1\n0 0\n1 0\nyes\n\
+                                     0 0\n0 1\n3 3\n1 0\ny\n\
+                                     pass\npass\n");
+        assert_eq!(GameOutcome::Draw, nogo.run_with(&mut input).unwrap());
+        assert_eq!(Scoreboard { o_wins: 1, x_wins: 1, draws: 1 }, nogo.get_scoreboard());
+        assert_eq!("Standings: Alice (O) 1, Player X 1, draws 1", nogo.standings());
+
+        // An abandoned game isn't counted and nobody is asked to play again.
+        let mut nogo  = Nogo::from_args(args(&["--rematch", "h", "h", "4", "4"])).unwrap();
+        let mut input = Cursor::new("quit\ny\n");
+        assert_eq!(GameOutcome::Aborted, nogo.run_with(&mut input).unwrap());
+        assert_eq!(Scoreboard::default(), nogo.get_scoreboard());
+        assert_eq!(1, nogo.lines_read);
+
+        // Without --rematch only one game is played.
+        let mut nogo = Nogo::from_args(args(&["--quiet", "c", "c", "4", "4"])).unwrap();
+        nogo.run_with(&mut Cursor::new("y\n")).unwrap();
+        assert_eq!(Scoreboard::default(), nogo.get_scoreboard());
+    }
+
+    #[test]
+    fn test_move_timeout() {
+        let short = Duration::from_millis(20);
+
+        // Lines come through whole and the end of input is still the end.
+        let (sender, lines) = mpsc::channel();
+        let mut input = TimedInput::new(lines, short);
+        sender.send(String::from("1 1\n")).unwrap();
+        let mut line = String::new();
+        input.read_line(&mut line).unwrap();
+        assert_eq!("1 1\n", line);
+        assert_eq!(io::ErrorKind::TimedOut, input.read_line(&mut line).unwrap_err().kind());
+        drop(sender);
+        assert_eq!(0, input.read_line(&mut line).unwrap());
+
+        // O moves then X runs out of time, losing.
+        let mut nogo = Nogo::from_args(args(&["--quiet", "--timeout-rule", "lose", "h", "h", "4", "4"])).unwrap();
+        let (sender, lines) = mpsc::channel();
+        sender.send(String::from("1 1\n")).unwrap();
+        assert_eq!(GameOutcome::Winner(Player::O), nogo.run_with(&mut TimedInput::new(lines, short)).unwrap());
+        assert_eq!(1, nogo.lines_read);
+
+        // Passing is the default, so both running out of time is a draw.
+        let mut nogo = Nogo::from_args(args(&["--quiet", "h", "h", "4", "4"])).unwrap();
+        let (sender, lines) = mpsc::channel();
+        sender.send(String::from("1 1\n")).unwrap();
+        assert_eq!(GameOutcome::Draw, nogo.run_with(&mut TimedInput::new(lines, short)).unwrap());
+        drop(sender);
+
+        let config = Config::parse(args(&["--move-timeout", "30", "--timeout-rule", "lose", "h", "h", "4", "4"]).into_iter()).unwrap();
+        assert_eq!(Some(Duration::from_secs(30)), config.move_timeout);
+        assert_eq!(TimeoutRule::Lose, config.timeout_rule);
+        assert_eq!(None, Config::parse(args(&["h", "h", "4", "4"]).into_iter()).unwrap().move_timeout);
+        for bad in &[&["--move-timeout", "0"][..], &["--move-timeout", "soon"], &["--timeout-rule", "draw"]] {
+            let mut bad = bad.to_vec();
+            bad.extend(&["h", "h", "4", "4"]);
+            assert!(Config::parse(args(&bad).into_iter()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_number_flags() {
+        let config = Config::parse(args(&["--log-size", "3", "--ai-seed", "9", "h", "h", "4", "4"]).into_iter()).unwrap();
+        assert_eq!(3, config.log_size);
+        assert_eq!(9, config.ai_seed);
+
+        for &(flag, bad) in &[("--move-timeout", "soon"), ("--move-timeout", "0"), ("--move-timeout", "-5"),
+                              ("--log-size", "many"), ("--log-size", "-1"), ("--ai-seed", "1.5")] {
+            match Config::parse(args(&[flag, bad, "h", "h", "4", "4"]).into_iter()) {
+                Err(e @ NogoError::NumArg(Some(_))) => {
+                    assert!(e.to_string().starts_with(&format!("Invalid {}\nUsage:", flag)), "{} {} gave {}", flag, bad, e);
+                },
+                r => panic!("expected {} error for {}, got {:?}", flag, bad, r),
+            }
+        }
 
-        match current_player {
-            Player::O => panic!("not equal"),
-            Player::X => assert!(true),
+        // A missing number is still just the usage.
+        match Config::parse(args(&["h", "h", "4", "4", "--ai-seed"]).into_iter()) {
+            Err(NogoError::NumArg(None)) => {},
+            r => panic!("expected usage, got {:?}", r),
         }
     }
+
+    #[test]
+    fn test_computer_forced_pass() {
+        let file = TempFile::new("forced_pass.txt");
+        let path = file.path();
+
+        // Both holes are O suicides so the computer passes, then X passes too.
+        ::std::fs::write(path, "nogo v1 4 4 0 0 0 0 0 0 0\nX.XX\nXXXX\nXX.X\nXXXX\n").unwrap();
+        let mut nogo = Nogo::from_args(args(&["--strength", "1", "c", "h", path])).unwrap();
+        assert_eq!(GameOutcome::Draw, nogo.run_with(&mut Cursor::new("pass\n")).unwrap());
+    }
+
+    #[test]
+    fn test_set_strategy() {
+        #[derive(Debug)]
+        struct Passer;
+
+        impl Strategy for Passer {
+            fn next_move(&mut self, _board: &GameBoard, _player: &Player) -> Result<Option<(usize, usize)>, NogoError> {
+                Ok(None)
+            }
+        }
+
+        let mut nogo = Nogo::from_args(args(&["c", "c", "4", "4"])).unwrap();
+        nogo.set_strategy(Player::O, Box::new(Passer));
+        nogo.set_strategy(Player::X, Box::new(Passer));
+        assert_eq!(GameOutcome::Draw, nogo.run_with(&mut Cursor::new("")).unwrap());
+    }
+
+    #[test]
+    fn test_undo_command() {
+        let file = TempFile::new("undo.txt");
+        let path = file.path();
+
+        let mut nogo = Nogo::from_args(args(&["--save-on-exit", "--save", path,
+                                        "h", "c", "5", "5"])).unwrap();
+        nogo.autosave = None;
+
+        // Undo takes back the computer's (2, 0) and the human's (2, 2). The
+        // computer answers (3, 3) with the next move of its sequence.
+        nogo.run_with(&mut Cursor::new("2 2\nundo\n3 3\nquit\n")).unwrap();
+
+        let (board, _, _, player) = nogo.load(path).unwrap();
+        assert_eq!(Some('.'), board.get(2, 2));
+        assert_eq!(Some('.'), board.get(2, 0));
+        assert_eq!(Some('O'), board.get(3, 3));
+        assert_eq!(Some('X'), board.get(3, 1));
+        assert_eq!(Player::O, player);
+    }
+
+    #[test]
+    fn test_move_number() {
+        let mut nogo = Nogo::from_args(args(&["h", "h", "4", "4"])).unwrap();
+        assert_eq!("Move 1, Player O> ", nogo.prompt_text(&Player::O));
+
+        // Only the two placements count, and the undo takes one back. The
+        // taken cell, help, log, order, and pass don't.
+        nogo.run_with(&mut Cursor::new("1 1\n1 1\nhelp\nlog\norder\npass\n2 2\n3 3\nundo\nquit\n")).unwrap();
+        assert_eq!(3, nogo.move_number);
+        assert_eq!("Move 3, Player X> ", nogo.prompt_text(&Player::X));
+    }
+
+    #[test]
+    fn test_help_command() {
+        let file = TempFile::new("help.txt");
+        let path = file.path();
+
+        let mut nogo = Nogo::from_args(args(&["--batch", "--save-on-exit", "--save", path,
+                                        "h", "h", "4", "4"])).unwrap();
+        nogo.autosave = None;
+
+        // Neither help uses up O's turn.
+        nogo.run_with(&mut Cursor::new("help\n?\n1 1\nquit\n")).unwrap();
+
+        let (board, _, _, player) = nogo.load(path).unwrap();
+        assert_eq!(Some('O'), board.get(1, 1));
+        assert_eq!(Player::X, player);
+
+        let help = nogo.help_text();
+        assert!(help.starts_with("row column  place a stone"));
+        assert!(help.contains("w file      save"));
+    }
+
+    #[test]
+    fn test_config_source() {
+        let dims = |height, width| BoardSource::Dimensions { height, width };
+        let file = |name: &str| BoardSource::File(String::from(name));
+
+        let cases = [
+            (vec!["h", "c", "7", "9"],              dims(7, 9)),
+            (vec!["h", "c", " 7", "9", "O"],        dims(7, 9)),
+            (vec!["h", "c", "medium"],              dims(13, 13)),
+            (vec!["h", "c", "9"],                   dims(9, 9)),
+            (vec!["h", "c", "9", "7"],              dims(9, 7)),
+            (vec!["h", "c", "9.txt"],               file("9.txt")),
+            (vec!["c", "c", "save.txt"],            file("save.txt")),
+            (vec!["--analyze", "save.txt"],         file("save.txt")),
+        ];
+
+        for (list, source) in cases.iter() {
+            assert_eq!(*source, Config::parse(args(list).into_iter()).unwrap().source);
+        }
+    }
+
+    #[test]
+    fn test_config_errors() {
+        // Compared by the start of their Debug output.
+        let cases: [(&[&str], &str); 5] = [
+            (&["h", "a", "7", "7"],             "IncorrectType"),
+            (&["h", "c", "7", "seven"],         "Parse"),
+            (&["h", "c"],                       "NumArg(Some"),
+            (&["h", "c", "7", "7", "O", "X"],   "NumArg(None)"),
+            (&["--strength"],                   "NumArg(None)"),
+        ];
+
+        for (list, expected) in cases.iter() {
+            let e = format!("{:?}", Config::parse(args(list).into_iter()).unwrap_err());
+            assert!(e.starts_with(expected), "{:?} gave {}", list, e);
+        }
+    }
+
+    #[test]
+    fn test_builder() {
+        let nogo = NogoBuilder::new().player1('h').player2('c').dimensions(7, 7).build().unwrap();
+        assert_eq!(7, nogo.get_height());
+        assert_eq!(7, nogo.get_width());
+        assert_eq!('h', nogo.get_p1type());
+        assert_eq!('c', nogo.get_p2type());
+        assert!(!nogo.is_file);
+
+        match NogoBuilder::new().dimensions(2, 2).build() {
+            Err(NogoError::InvalidDimension) => {},
+            r => panic!("expected invalid dimension, got {:?}", r),
+        }
+
+        match NogoBuilder::new().player1('x').dimensions(7, 7).build() {
+            Err(NogoError::IncorrectType) => {},
+            r => panic!("expected incorrect type, got {:?}", r),
+        }
+
+        let nogo = NogoBuilder::new().from_file(String::from("save.txt")).build().unwrap();
+        assert!(nogo.is_file);
+        assert!(NogoBuilder::new().build().is_err());
+    }
+
+    #[test]
+    fn test_presets() {
+        let nogo = Nogo::from_args(args(&["h", "c", "small"])).unwrap();
+        assert_eq!(7, nogo.get_height());
+        assert_eq!(7, nogo.get_width());
+        assert!(!nogo.is_file);
+
+        let nogo = Nogo::from_args(args(&["h", "c", "large"])).unwrap();
+        assert_eq!(19, nogo.get_height());
+
+        // Names are only understood in place of both dimensions.
+        match Nogo::from_args(args(&["h", "c", "huge", "7"])) {
+            Err(NogoError::Parse(_)) => {},
+            r => panic!("expected parse error, got {:?}", r),
+        }
+
+        // Anything else on its own is still a save file.
+        let nogo = Nogo::from_args(args(&["h", "c", "huge"])).unwrap();
+        assert!(nogo.is_file);
+    }
+
+    #[test]
+    fn test_extra_values() {
+        let mut nogo  = Nogo::from_args(args(&["h", "h", "7", "7"])).unwrap();
+        let board     = GameBoard::new(7, 7).unwrap();
+        let mut input = Cursor::new("3 4 5\n1 2 # note\n");
+
+        assert_eq!(Move::Place(1, 2), nogo.get_move(&mut input, &board, None, None, &Player::O).unwrap());
+    }
+
+    #[test]
+    fn test_log_command() {
+        let mut nogo  = Nogo::from_args(args(&["--log-size", "2", "h", "h", "7", "7"])).unwrap();
+        let mut board = GameBoard::new(7, 7).unwrap();
+        board.insert_move(0, 0, &Player::O).unwrap();
+        board.insert_move(1, 1, &Player::X).unwrap();
+        board.insert_move(2, 3, &Player::O).unwrap();
+
+        assert_eq!("2: X 1 1\n3: O 2 3", Nogo::move_log(&board, nogo.log_size));
+        assert_eq!("1: O 0 0\n2: X 1 1\n3: O 2 3", Nogo::move_log(&board, 10));
+        assert_eq!("", Nogo::move_log(&GameBoard::new(7, 7).unwrap(), 10));
+
+        // Doesn't use up the player's turn.
+        let mut input = Cursor::new("log\n4 4\n");
+        assert_eq!(Move::Place(4, 4), nogo.get_move(&mut input, &board, None, None, &Player::X).unwrap());
+    }
+
+    #[test]
+    fn test_save_format_tag() {
+        let file = TempFile::new("format_tag.txt");
+        let path = file.path();
+
+        ::std::fs::write(path, "nogo v1 4 4 1 2 3 4 0 0 0\n\
+                                O...\n\
+                                ....\n\
+                                ....\n\
+                                ....\n").unwrap();
+
+        let mut nogo = Nogo::from_args(args(&["c", "h", path])).unwrap();
+        let (board, c1, _, player) = nogo.load(path).unwrap();
+        assert_eq!(Some('O'), board.get(0, 0));
+        assert_eq!(4, c1.unwrap().get_counter());
+        assert_eq!(Player::X, player);
+
+        for header in ["nogo v2 4 4 1 2 3 4 0 0 0", "nogo v10 4 4 1 2 3 4 0 0 0", "4 4 1 2 3 4 0 0 0"].iter() {
+            ::std::fs::write(path, format!("{}\n....\n....\n....\n....\n", header)).unwrap();
+
+            match nogo.load(path) {
+                Err(NogoError::CorruptFile { .. }) => {},
+                r => panic!("expected corrupt file for {}, got {:?}", header, r.map(|_| ())),
+            }
+            assert!(Computer::load(&nogo, header, Player::O).is_err());
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let file = TempFile::new("round_trip.json");
+        let path = file.path();
+
+        let mut nogo  = Nogo::from_args(args(&["c", "h", "5", "6"])).unwrap();
+        let mut board = GameBoard::new(5, 6).unwrap();
+        let mut c1    = Computer::new(&nogo, Player::O).unwrap();
+
+        for _ in 0..3 {
+            let (h, w) = c1.choose_move(&board, &Player::O).unwrap().unwrap();
+            board.insert_move(h, w, &Player::O).unwrap();
+        }
+        board.insert_move(4, 5, &Player::X).unwrap();
+
+        nogo.save_json(path, &board, Some(&c1), None, &Player::O).unwrap();
+        let (loaded, l1, l2, player) = nogo.load_json(path).unwrap();
+
+        assert_eq!(board.render(), loaded.render());
+        let l1 = l1.unwrap();
+        assert_eq!((c1.get_row(), c1.get_column(), c1.get_counter()),
+                   (l1.get_row(), l1.get_column(), l1.get_counter()));
+        assert!(l2.is_none());
+        assert_eq!(Player::O, player);
+        assert_eq!((5, 6), (nogo.get_height(), nogo.get_width()));
+
+        // Both computers carry on with the same moves.
+        let mut l1 = l1;
+        assert_eq!(c1.choose_move(&board, &Player::O).unwrap(), l1.choose_move(&loaded, &Player::O).unwrap());
+
+        ::std::fs::write(path, "{\"format\": \"nogo v2\"}").unwrap();
+        assert!(nogo.load_json(path).is_err());
+    }
+
+    #[test]
+    fn test_sequence_start() {
+        let file = TempFile::new("sequence_start.txt");
+        let path = file.path();
+
+        let plain      = Nogo::from_args(args(&["c", "c", "7", "7"])).unwrap();
+        let nogo       = Nogo::from_args(args(&["--x-start", "3,0,5", "c", "c", "7", "7"])).unwrap();
+        let mut board  = GameBoard::new(7, 7).unwrap();
+
+        assert_eq!(plain.get_sequence_start(Player::O), nogo.get_sequence_start(Player::O));
+        assert_eq!(SequenceStart { row: 3, column: 0, mult_factor: 5 }, nogo.get_sequence_start(Player::X));
+
+        let mut default = DeterministicStrategy::new(&plain, Player::X);
+        let mut moved   = DeterministicStrategy::new(&nogo, Player::X);
+        let default     = (0..10).map(|_| default.get_and_generate_move()).collect::<Vec<_>>();
+        let moved       = (0..10).map(|_| moved.get_and_generate_move()).collect::<Vec<_>>();
+        assert_eq!((3, 0), moved[0]);
+        assert_ne!(default, moved);
+
+        // A loaded game carries on past the fifth move's jump.
+        let mut c2 = Computer::new(&nogo, Player::X).unwrap();
+        for _ in 0..4 {
+            let (h, w) = c2.choose_move(&board, &Player::X).unwrap().unwrap();
+            board.insert_move(h, w, &Player::X).unwrap();
+        }
+
+        nogo.save(path, &board, None, Some(&c2), &Player::X).unwrap();
+        let mut loader = Nogo::from_args(args(&["c", "c", path])).unwrap();
+        let (loaded, _, l2, _) = loader.load(path).unwrap();
+        let mut l2 = l2.unwrap();
+
+        assert_eq!(nogo.get_sequence_start(Player::X), loader.get_sequence_start(Player::X));
+        for _ in 0..5 {
+            assert_eq!(c2.choose_move(&board, &Player::X).unwrap(), l2.choose_move(&loaded, &Player::X).unwrap());
+        }
+
+        // JSON saves keep it too.
+        nogo.save_json(path, &board, None, Some(&c2), &Player::X).unwrap();
+        let mut loader = Nogo::from_args(args(&["c", "c", path])).unwrap();
+        loader.load_json(path).unwrap();
+        assert_eq!(nogo.get_sequence_start(Player::X), loader.get_sequence_start(Player::X));
+        assert_eq!(plain.get_sequence_start(Player::O), loader.get_sequence_start(Player::O));
+
+        ::std::fs::remove_file(path).unwrap();
+
+        for bad in &["3,0", "3,0,5,1", "a,0,5", "3,0,1000003"] {
+            assert!(Config::parse(args(&["--o-start", bad, "c", "c", "7", "7"]).into_iter()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_ai_seed_save() {
+        let file = TempFile::new("ai_seed.txt");
+        let path = file.path();
+
+        let nogo      = Nogo::from_args(args(&["--ai-seed", "7", "c", "c", "7", "7"])).unwrap();
+        let mut board = GameBoard::new(7, 7).unwrap();
+        let mut c1    = Computer::new(&nogo, Player::O).unwrap();
+        for _ in 0..3 {
+            let (h, w) = c1.choose_move(&board, &Player::O).unwrap().unwrap();
+            board.insert_move(h, w, &Player::O).unwrap();
+        }
+
+        // The seed follows the usual sequence starts.
+        nogo.save(path, &board, Some(&c1), None, &Player::O).unwrap();
+        let saved = ::std::fs::read_to_string(path).unwrap();
+        assert!(saved.starts_with("nogo v1 7 7 0 "));
+        assert!(saved.lines().next().unwrap().ends_with(" 1 4 29 2 10 17 7"));
+
+        let mut loader = Nogo::from_args(args(&["c", "c", path])).unwrap();
+        let (loaded, l1, _, _) = loader.load(path).unwrap();
+        let mut l1 = l1.unwrap();
+        assert_eq!(7, loader.get_ai_seed());
+        for _ in 0..5 {
+            assert_eq!(c1.choose_move(&board, &Player::O).unwrap(), l1.choose_move(&loaded, &Player::O).unwrap());
+        }
+
+        nogo.save_json(path, &board, Some(&c1), None, &Player::O).unwrap();
+        let mut loader = Nogo::from_args(args(&["c", "c", path])).unwrap();
+        loader.load_json(path).unwrap();
+        assert_eq!(7, loader.get_ai_seed());
+
+        // Without a seed saves are as they always were.
+        let plain = Nogo::from_args(args(&["c", "c", "7", "7"])).unwrap();
+        plain.save(path, &board, None, None, &Player::O).unwrap();
+        assert!(::std::fs::read_to_string(path).unwrap().starts_with("nogo v1 7 7 0 0 0 0 0 0 0\n"));
+        plain.save_json(path, &board, None, None, &Player::O).unwrap();
+        assert!(!::std::fs::read_to_string(path).unwrap().contains("ai_seed"));
+    }
+
+    #[test]
+    fn test_corrupt_file_context() {
+        let file = TempFile::new("corrupt_context.txt");
+        let path = file.path();
+        let mut nogo = Nogo::from_args(args(&["h", "h", path])).unwrap();
+
+        ::std::fs::write(path, "nogo v1 4 4\n....\n....\n....\n....\n").unwrap();
+        let e = nogo.load(path).map(|_| ()).unwrap_err();
+        assert_eq!("Incorrect file contents on line 1: header missing next-player field", e.to_string());
+
+        ::std::fs::write(path, "nogo v1 4 4 0 0 0 0 0 0 0\n....\n....\n...\n....\n").unwrap();
+        let e = nogo.load(path).map(|_| ()).unwrap_err();
+        assert_eq!("Incorrect file contents on line 4: row 2 has wrong width", e.to_string());
+
+        ::std::fs::write(path, "nogo v1 5 4 0 0 0 0 0 0 0\n....\n....\n....\n....\n").unwrap();
+        let e = nogo.load(path).map(|_| ()).unwrap_err();
+        assert_eq!("Incorrect file contents: board is 4x4 but should be 5x4", e.to_string());
+    }
+
+    #[test]
+    fn test_strict_load() {
+        let file = TempFile::new("strict_load.txt");
+        let path = file.path();
+
+        ::std::fs::write(path, "nogo v1 4 4 1 0 0 0 0 0 0\n\
+                                XO..\n\
+                                O...\n\
+                                ...O\n\
+                                ..OX\n").unwrap();
+
+        let mut nogo = Nogo::from_args(args(&["h", "h", path])).unwrap();
+        assert!(nogo.load(path).is_ok());
+
+        let mut nogo = Nogo::from_args(args(&["--strict", "h", "h", path])).unwrap();
+        match nogo.load(path) {
+            Err(NogoError::CorruptFile { .. }) => {},
+            r => panic!("expected corrupt file, got {:?}", r.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_empty_glyph() {
+        let nogo = Nogo::from_args(args(&["--empty", "+", "h", "h", "7", "7"])).unwrap();
+        assert_eq!('+', nogo.empty_glyph);
+
+        assert!(Nogo::from_args(args(&["--empty", "++", "h", "h", "7", "7"])).is_err());
+        assert!(Nogo::from_args(args(&["--empty", "X", "h", "h", "7", "7"])).is_err());
+    }
+
+    #[test]
+    fn test_labels() {
+        let nogo  = Nogo::from_args(args(&["h", "h", "4", "4"])).unwrap();
+        let board = GameBoard::new(4, 4).unwrap();
+        assert_eq!(board.render(), nogo.render(&board));
+
+        let nogo = Nogo::from_args(args(&["--labels", "h", "h", "4", "4"])).unwrap();
+        assert_eq!(board.render_labeled('.'), nogo.render(&board));
+
+        let mut board = board;
+        board.insert_move(2, 1, &Player::X).unwrap();
+        let nogo = Nogo::from_args(args(&["--labels", "--highlight", "h", "h", "4", "4"])).unwrap();
+        assert!(nogo.render(&board).contains("\n2|.x..|\n"));
+
+        // Other symbols are highlighted the same way.
+        let nogo = Nogo::from_args(args(&["--highlight", "--x-symbol", "B", "h", "h", "4", "4"])).unwrap();
+        assert!(nogo.render(&board).contains("\n|.b..|\n"));
+
+        // Symbols without a lowercase form of their own can't be highlighted.
+        for bad in &[&["--o-symbol", "@"][..], &["--x-symbol", "b"], &["--o-symbol", "1"],
+                     &["--empty", "o"], &["--empty", "a", "--x-symbol", "A"]] {
+            let mut list = vec!["--highlight"];
+            list.extend(bad.iter());
+            list.extend(&["h", "h", "4", "4"]);
+            assert!(Nogo::from_args(args(&list)).is_err(), "{:?}", bad);
+
+            // They are fine without --highlight.
+            assert!(Nogo::from_args(args(&list[1..])).is_ok(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_seed_board() {
+        let nogo = Nogo::from_args(args(&["h", "h", "4", "4", "O..............X"])).unwrap();
+        let (board, first) = nogo.start_board().unwrap();
+        assert_eq!(Some('O'), board.get(0, 0));
+        assert_eq!(Some('X'), board.get(3, 3));
+        assert_eq!(Some('.'), board.get(1, 1));
+        assert_eq!(Player::O, first);
+
+        let nogo = Nogo::from_args(args(&["h", "h", "4", "4", "O..............."])).unwrap();
+        assert_eq!(Player::X, nogo.start_board().unwrap().1);
+
+        match Nogo::from_args(args(&["h", "h", "4", "4", "O...."])) {
+            Err(NogoError::InvalidDimension) => {},
+            r => panic!("expected invalid dimension, got {:?}", r),
+        }
+
+        // Bad cells and games that are already over.
+        for cells in &["O..............Q", "XO..O..........."] {
+            match Nogo::from_args(args(&["h", "h", "4", "4", cells])) {
+                Err(NogoError::CorruptFile { .. }) => {},
+                r => panic!("expected corrupt file, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn test_missing_board() {
+        match Nogo::from_args(args(&["c", "c"])) {
+            Err(e @ NogoError::NumArg(Some(_))) => {
+                assert!(e.to_string().starts_with("Missing board dimensions or save file\nUsage:"));
+            },
+            r => panic!("expected missing board error, got {:?}", r),
+        }
+
+        match Nogo::from_args(args(&["c"])) {
+            Err(NogoError::NumArg(None)) => {},
+            r => panic!("expected usage error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_strength_flag() {
+        let nogo = Nogo::from_args(args(&["--strength", "3", "c", "c", "7", "7"])).unwrap();
+        assert_eq!(3, nogo.get_strength());
+
+        for bad in &["4", "-1", "x"] {
+            match Nogo::from_args(args(&["--strength", bad, "c", "c", "7", "7"])) {
+                Err(e @ NogoError::NumArg(Some(_))) => {
+                    assert!(e.to_string().starts_with("Invalid --strength\nUsage:"));
+                },
+                r => panic!("expected strength error, got {:?}", r),
+            }
+        }
+    }
+
+    #[test]
+    fn test_analysis() {
+        let board = GameBoard::from("XO..\n\
+                                     ....\n\
+                                     ..OO\n\
+                                     ..OX\n").unwrap();
+
+        assert_eq!("Groups: 4\n\
+                    X group at 0 0: 1 stone, 1 liberty (atari)\n\
+                    O group at 0 1: 1 stone, 2 liberties\n\
+                    O group at 2 2: 3 stones, 4 liberties\n\
+                    X group at 3 3: 1 stone, 0 liberties\n\
+                    Atari: 1\n\
+                    Mobility: O 10, X 10\n\
+                    Status: player O wins", Nogo::analysis(&board, &Player::X).unwrap());
+
+        assert!(Nogo::from_args(args(&["--analyze", "save.txt", "h"])).is_err());
+    }
+
+    #[test]
+    fn test_winner_at() {
+        let board = GameBoard::from("XO..\n\
+                                     O...\n\
+                                     ....\n\
+                                     ....\n").unwrap();
+
+        assert_eq!(Player::O, Nogo::winner_at(&board, 0, 0).unwrap());
+        assert_eq!(Player::X, Nogo::winner_at(&board, 0, 1).unwrap());
+
+        match Nogo::winner_at(&board, 3, 3) {
+            Err(NogoError::Internal(msg)) => assert_eq!("no stone at winning cell 3 3", msg),
+            _ => panic!("expected an internal error"),
+        }
+    
+        assert!(Nogo::winner_at(&board, 4, 0).is_err());
+    }
+
+    #[test]
+    fn test_order_command() {
+        let mut nogo  = Nogo::from_args(args(&["h", "h", "7", "7"])).unwrap();
+        let board     = GameBoard::new(7, 7).unwrap();
+        let mut input = Cursor::new("3 4\norder\n3 4\norder\n3 4\n");
+
+        assert_eq!(Move::Place(3, 4), nogo.get_move(&mut input, &board, None, None, &Player::O).unwrap());
+        assert_eq!(Move::Place(4, 3), nogo.get_move(&mut input, &board, None, None, &Player::X).unwrap());
+        assert_eq!(nogo.coord_order, CoordOrder::ColumnFirst);
+        assert_eq!(Move::Place(3, 4), nogo.get_move(&mut input, &board, None, None, &Player::O).unwrap());
+    }
 }
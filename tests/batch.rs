@@ -0,0 +1,223 @@
+use std::io::Write;
+use std::process::Command;
+use std::process::Output;
+use std::process::Stdio;
+
+/// Runs nogors with args, piping moves in on standard input.
+fn run(args: &[&str], moves: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_nogors"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Fails with a broken pipe if nogors finished without reading them all,
+    // which the output shows anyway.
+    let _ = child.stdin.take().unwrap().write_all(moves.as_bytes());
+    child.wait_with_output().unwrap()
+}
+
+/// File in the temp directory, deleted when dropped so a failing test
+/// doesn't leave it behind.
+struct TempFile(std::path::PathBuf);
+
+impl TempFile {
+    fn new(name: &str) -> TempFile {
+        let name = format!("nogors_batch_{}_{}", std::process::id(), name);
+        TempFile(std::env::temp_dir().join(name))
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn test_batch_winner() {
+    // O takes the last liberties of X's corner stone.
+    let output = run(&["--batch", "h", "h", "4", "4"], "0 1\n0 0\n1 0\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.ends_with("Player O wins!\n"));
+}
+
+#[test]
+fn test_quiet() {
+    let output = run(&["--quiet", "c", "c", "7", "7"], "");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(1, stdout.lines().count(), "{}", stdout);
+    assert!(stdout.starts_with("Player "));
+
+    // Human prompts are left out too.
+    let output = run(&["--quiet", "h", "h", "4", "4"], "1 1\npass\npass\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("Draw!\n", stdout);
+}
+
+#[test]
+fn test_missing_file() {
+    let output = run(&["h", "h", "nogors_no_such_save.txt"], "");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(Some(4), output.status.code());
+    assert_eq!("Unable to open file: nogors_no_such_save.txt (not found)\n", stderr);
+}
+
+#[test]
+fn test_batch_bad_line() {
+    let output = run(&["--batch", "h", "h", "4", "4"], "0 1\n0 0\n0 0\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(Some(6), output.status.code());
+    assert_eq!("Invalid move on line 3: Position already taken\n", stderr);
+
+    let output = run(&["--batch", "h", "h", "4", "4"], "0 1\nzero 0\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(Some(6), output.status.code());
+    assert!(stderr.starts_with("Invalid move on line 2:"));
+}
+
+#[test]
+fn test_reprompt_bad_cell() {
+    // Without --batch the same player is asked again.
+    let output = run(&["--quiet", "h", "h", "4", "4"], "0 0\n0 0\n9 0\n1 1\npass\npass\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!("Error: Position already taken\nError: Invalid row\n", stderr);
+}
+
+#[test]
+fn test_analyze_save() {
+    let file = TempFile::new("analyze.txt");
+    let path = file.path();
+    std::fs::write(path, "nogo v1 4 4 1 0 0 0 0 0 0\n\
+                           XO..\n\
+                           ....\n\
+                           ..OO\n\
+                           ..OX\n").unwrap();
+
+    let output = run(&["--analyze", path], "");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.ends_with("Groups: 4\n\
+                              X group at 0 0: 1 stone, 1 liberty (atari)\n\
+                              O group at 0 1: 1 stone, 2 liberties\n\
+                              O group at 2 2: 3 stones, 4 liberties\n\
+                              X group at 3 3: 1 stone, 0 liberties\n\
+                              Atari: 1\n\
+                              Mobility: O 10, X 10\n\
+                              Status: player O wins\n"));
+    assert!(!stdout.contains("Player X>"));
+}
+
+#[test]
+fn test_load_finished_game() {
+    let file = TempFile::new("finished.txt");
+    let path = file.path();
+    std::fs::write(path, "nogo v1 4 4 1 0 0 0 0 0 0\n\
+                           XO..\n\
+                           O...\n\
+                           ....\n\
+                           ....\n").unwrap();
+
+    // The game is over before any move is read.
+    let output = run(&["h", "h", path], "2 2\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!("/----\\\n\
+                |XO..|\n\
+                |O...|\n\
+                |....|\n\
+                |....|\n\
+                \\----/\n\
+                Player O wins!\n", stdout);
+}
+
+#[test]
+fn test_save_round_trip() {
+    let first  = TempFile::new("round_trip_1.txt");
+    let second = TempFile::new("round_trip_2.txt");
+    let (first_path, second_path) = (first.path(), second.path());
+
+    // Two computer moves and a human one in, so the computer has moved
+    // along its sequence.
+    let output = run(&["--quiet", "c", "h", "7", "7"], &format!("3 3\nw {}\nquit\n", first_path));
+    assert!(output.status.success());
+
+    let saved = std::fs::read_to_string(first_path).unwrap();
+    assert_eq!(8, saved.lines().count(), "{}", saved);
+    assert!(saved.ends_with('\n') && !saved.contains("\n\n"));
+
+    // Loading and saving straight away gives back the same file, even with
+    // blank lines left at the end.
+    std::fs::write(first_path, format!("{}\n\n", saved)).unwrap();
+    let output = run(&["--quiet", "c", "h", first_path], &format!("w {}\nquit\n", second_path));
+    assert!(output.status.success());
+
+    let resaved = std::fs::read_to_string(second_path).unwrap();
+    assert_eq!(saved, resaved);
+}
+
+#[test]
+fn test_explain_ai() {
+    // The computer O explains its move. The human X quits straight away.
+    let output = run(&["--explain-ai", "c", "h", "4", "4"], "quit\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("AI O: chose (1, 0) with 3 liberties\nMove 1, Player O> 1 0\n"));
+    assert_eq!(1, stdout.matches("AI ").count());
+
+    let output = run(&["--explain-ai", "h", "h", "4", "4"], "0 0\nquit\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(!stdout.contains("AI "));
+}
+
+#[test]
+fn test_pass() {
+    let output = run(&["--batch", "h", "h", "4", "4"], "pass\npass\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.ends_with("Move 1, Player X> Draw!\n"));
+
+    // O passes twice but X moves in between, so the game goes on.
+    let output = run(&["--batch", "h", "h", "4", "4"], "pass\n0 0\npass\n1 1\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(!stdout.contains("Draw!"));
+    assert!(stdout.contains("|X...|\n|.X..|\n"));
+}
+
+#[test]
+fn test_timings_any_ending() {
+    // Draws and abandoned games get the breakdown as well as wins.
+    for moves in &["pass\npass\n", "1 1\nquit\n", "", "0 1\n0 0\n1 0\n"] {
+        let output = run(&["--timings", "h", "h", "4", "4"], moves);
+        let stdout = String::from_utf8(output.stdout).unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(1, stdout.matches("Timings:").count(), "{:?}: {}", moves, stdout);
+    }
+
+    let output = run(&["h", "h", "4", "4"], "pass\npass\n");
+    assert!(!String::from_utf8(output.stdout).unwrap().contains("Timings:"));
+}
@@ -0,0 +1,47 @@
+extern crate nogors;
+
+use nogors::BoardSource;
+use nogors::Computer;
+use nogors::Config;
+use nogors::GameBoard;
+use nogors::Nogo;
+use nogors::Player;
+
+#[test]
+fn test_play_without_binary() {
+    let mut board = GameBoard::new(4, 4).unwrap();
+
+    board.insert_move(0, 1, &Player::O).unwrap();
+    board.insert_move(1, 0, &Player::O).unwrap();
+    assert_eq!(None, board.check_win());
+
+    // X in the corner has no liberties.
+    board.insert_move(0, 0, &Player::X).unwrap();
+    assert_eq!(Some((0, 0)), board.check_win_at(0, 0));
+}
+
+#[test]
+fn test_computer_without_binary() {
+    let args = ["nogors", "h", "c", "5", "5"].iter().map(|s| s.to_string());
+    let nogo = Nogo::from_args(args).unwrap();
+    let board = GameBoard::new(5, 5).unwrap();
+
+    assert!(Computer::new(&nogo, Player::O).is_none());
+    let mut computer = Computer::new(&nogo, Player::X).unwrap();
+    let (h, w) = computer.choose_move(&board, &Player::X).unwrap().unwrap();
+    assert_eq!(Some('.'), board.get(h, w));
+}
+
+#[test]
+fn test_config_without_arguments() {
+    let config = Config {
+        p2:     'c',
+        source: BoardSource::Dimensions { height: 5, width: 9 },
+        ..Config::default()
+    };
+    let nogo = Nogo::new(config).unwrap();
+
+    assert_eq!(5, nogo.get_height());
+    assert_eq!(9, nogo.get_width());
+    assert!(Computer::new(&nogo, Player::X).is_some());
+}